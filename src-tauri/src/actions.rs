@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use crate::output::OutputSink;
+use crate::postprocess;
+use crate::settings::Settings;
+
+/// What a hotkey does with the recording. Each action adjusts the pipeline
+/// on top of the global settings and any per-application profile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DictationAction {
+    /// The pipeline exactly as configured.
+    #[default]
+    Dictate,
+    /// Dictation followed by the LLM cleanup pass, even if it is off.
+    Cleanup,
+    /// Whisper translates the speech to English.
+    Translate,
+    /// The speech is an instruction ("write a polite reply declining the
+    /// meeting") and the LLM's answer is what gets typed.
+    Command,
+}
+
+impl DictationAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DictationAction::Dictate => "dictate",
+            DictationAction::Cleanup => "cleanup",
+            DictationAction::Translate => "translate",
+            DictationAction::Command => "command",
+        }
+    }
+
+    pub fn apply(&self, settings: &mut Settings) {
+        match self {
+            DictationAction::Dictate => {}
+            DictationAction::Cleanup => {
+                settings.llm_cleanup_enabled = true;
+            }
+            DictationAction::Translate => {
+                settings.translate_mode = true;
+            }
+            DictationAction::Command => {
+                settings.llm_cleanup_enabled = true;
+                settings.llm_system_prompt = Some(postprocess::COMMAND_SYSTEM_PROMPT.to_string());
+                // The answer is prose written by the model; spoken commands
+                // and code casing would only mangle it.
+                settings.code_mode = false;
+                settings.formatting_commands_enabled = false;
+                settings.strip_trailing_period = false;
+            }
+        }
+    }
+}
+
+/// An extra hotkey, as stored in settings.json. The main `hotkey` setting is
+/// always bound to [`DictationAction::Dictate`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    pub hotkey: String,
+    pub action: DictationAction,
+    /// Where this hotkey's dictations go; `None` keeps the configured outputs.
+    #[serde(default)]
+    pub outputs: Option<Vec<OutputSink>>,
+}
+
+impl HotkeyBinding {
+    pub fn apply(&self, settings: &mut Settings) {
+        self.action.apply(settings);
+        if let Some(outputs) = &self.outputs {
+            settings.outputs = outputs.clone();
+        }
+    }
+}
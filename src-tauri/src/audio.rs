@@ -6,13 +6,14 @@ use tauri::{AppHandle, Emitter};
 use serde_json::json;
 use hound::{WavWriter, WavSpec};
 
-const TARGET_SAMPLE_RATE: u32 = 16000;
+pub const TARGET_SAMPLE_RATE: u32 = 16000;
 
 pub struct AudioProcessor {
     buffer: Arc<Mutex<Vec<f32>>>,
     wav_writer: Option<WavWriter<std::io::BufWriter<std::fs::File>>>,
     is_recording: Arc<Mutex<bool>>,
     downsample_ratio: f32,
+    stream_samples: Option<Vec<f32>>,
 }
 
 impl AudioProcessor {
@@ -28,6 +29,7 @@ impl AudioProcessor {
             wav_writer: None,
             is_recording: Arc::new(Mutex::new(false)),
             downsample_ratio,
+            stream_samples: None,
         }
     }
 
@@ -43,6 +45,7 @@ impl AudioProcessor {
         let filename = temp_dir.join("vwisper_audio_latest.wav");
         
         self.wav_writer = Some(WavWriter::create(&filename, spec)?);
+        self.stream_samples = None;
         *self.is_recording.lock().unwrap() = true;
         Ok(())
     }
@@ -93,6 +96,25 @@ impl AudioProcessor {
     pub fn is_recording(&self) -> bool {
         *self.is_recording.lock().unwrap()
     }
+
+    /// Keeps a copy of every recorded sample in memory so the streaming worker
+    /// can cut chunks out of the clip while the key is still held.
+    pub fn enable_streaming(&mut self) {
+        self.stream_samples = Some(Vec::new());
+    }
+
+    fn push_stream_samples(&mut self, samples: &[f32]) {
+        if let Some(stream) = &mut self.stream_samples {
+            stream.extend_from_slice(samples);
+        }
+    }
+
+    pub fn drain_stream_samples(&mut self) -> Vec<f32> {
+        match &mut self.stream_samples {
+            Some(stream) => std::mem::take(stream),
+            None => Vec::new(),
+        }
+    }
 }
 
 static AUDIO_PROCESSOR: std::sync::OnceLock<Arc<Mutex<Option<AudioProcessor>>>> = std::sync::OnceLock::new();
@@ -148,6 +170,7 @@ pub fn start_audio_capture(app_handle: AppHandle) {
                         if let Err(e) = processor.write_samples(&downsampled) {
                             eprintln!("Failed to write samples: {}", e);
                         }
+                        processor.push_stream_samples(&downsampled);
                     }
                 }
             },
@@ -192,4 +215,34 @@ pub fn is_recording() -> bool {
     } else {
         false
     }
-} 
\ No newline at end of file
+}
+
+pub fn enable_streaming() {
+    if let Some(processor) = &mut *get_audio_processor().lock().unwrap() {
+        processor.enable_streaming();
+    }
+}
+
+pub fn drain_stream_samples() -> Vec<f32> {
+    if let Some(processor) = &mut *get_audio_processor().lock().unwrap() {
+        processor.drain_stream_samples()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Writes 16 kHz mono samples to a standalone WAV file, used for streaming chunks.
+pub fn write_wav(path: &std::path::Path, samples: &[f32]) -> Result<(), Box<dyn std::error::Error>> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: TARGET_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec)?;
+    for &sample in samples {
+        writer.write_sample((sample * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
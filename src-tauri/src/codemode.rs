@@ -0,0 +1,272 @@
+use crate::formatting::Join;
+use crate::text::Token;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Casing {
+    Camel,
+    Pascal,
+    Snake,
+    Constant,
+    Kebab,
+    Lower,
+    Upper,
+}
+
+const CASINGS: &[(&str, Casing)] = &[
+    ("camel case", Casing::Camel),
+    ("pascal case", Casing::Pascal),
+    ("title case", Casing::Pascal),
+    ("snake case", Casing::Snake),
+    ("screaming snake case", Casing::Constant),
+    ("constant case", Casing::Constant),
+    ("kebab case", Casing::Kebab),
+    ("dash case", Casing::Kebab),
+    ("lower case", Casing::Lower),
+    ("all caps", Casing::Upper),
+];
+
+/// Spoken symbol names. `Join` decides the spacing: operators are spaced,
+/// brackets and member access are tight.
+const SYMBOLS: &[(&str, &str, Join)] = &[
+    ("open paren", "(", Join::Both),
+    ("close paren", ")", Join::Left),
+    ("open bracket", "[", Join::Both),
+    ("close bracket", "]", Join::Left),
+    ("open brace", "{", Join::Neither),
+    ("open curly", "{", Join::Neither),
+    ("close brace", "}", Join::Neither),
+    ("close curly", "}", Join::Neither),
+    ("open angle", "<", Join::Both),
+    ("close angle", ">", Join::Left),
+    ("arrow", "->", Join::Neither),
+    ("fat arrow", "=>", Join::Neither),
+    ("equals", "=", Join::Neither),
+    ("double equals", "==", Join::Neither),
+    ("triple equals", "===", Join::Neither),
+    ("not equals", "!=", Join::Neither),
+    ("plus equals", "+=", Join::Neither),
+    ("minus equals", "-=", Join::Neither),
+    ("less than", "<", Join::Neither),
+    ("greater than", ">", Join::Neither),
+    ("plus", "+", Join::Neither),
+    ("minus", "-", Join::Neither),
+    ("times", "*", Join::Neither),
+    ("star", "*", Join::Both),
+    ("slash", "/", Join::Both),
+    ("backslash", "\\", Join::Both),
+    ("double and", "&&", Join::Neither),
+    ("double pipe", "||", Join::Neither),
+    ("pipe", "|", Join::Neither),
+    ("ampersand", "&", Join::Right),
+    ("bang", "!", Join::Right),
+    ("question mark", "?", Join::Left),
+    ("dot", ".", Join::Both),
+    ("comma", ",", Join::Left),
+    ("colon", ":", Join::Left),
+    ("double colon", "::", Join::Both),
+    ("semicolon", ";", Join::Left),
+    ("underscore", "_", Join::Both),
+    ("dash", "-", Join::Both),
+    ("hash", "#", Join::Right),
+    ("at sign", "@", Join::Right),
+    ("dollar sign", "$", Join::Right),
+    ("percent sign", "%", Join::Left),
+    ("caret", "^", Join::Both),
+    ("tilde", "~", Join::Right),
+    ("backtick", "`", Join::Both),
+    ("open quote", "\"", Join::Right),
+    ("close quote", "\"", Join::Left),
+    ("single quote", "'", Join::Both),
+    ("new line", "\n", Join::Both),
+    ("tab", "\t", Join::Both),
+    ("space", " ", Join::Both),
+];
+
+enum Phrase {
+    Casing(Casing),
+    Symbol(&'static str, Join),
+}
+
+fn phrase_at(tokens: &[Token]) -> Option<(usize, Phrase)> {
+    let casings = CASINGS.iter().map(|(p, c)| (*p, Phrase::Casing(*c)));
+    let symbols = SYMBOLS.iter().map(|(p, s, j)| (*p, Phrase::Symbol(s, *j)));
+    casings
+        .chain(symbols)
+        .filter_map(|(phrase, kind)| {
+            let words: Vec<&str> = phrase.split_whitespace().collect();
+            let candidate = tokens.get(..words.len())?;
+            if candidate.iter().zip(&words).all(|(t, w)| t.key() == *w) {
+                Some((words.len(), kind))
+            } else {
+                None
+            }
+        })
+        // Longest match wins: "double equals" over "equals".
+        .max_by_key(|(len, _)| *len)
+}
+
+fn apply_casing(words: &[String], casing: Casing) -> String {
+    let capitalized = |w: &String| {
+        let mut chars = w.chars();
+        chars.next().map_or(String::new(), |c| c.to_uppercase().chain(chars).collect())
+    };
+    match casing {
+        Casing::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.clone() } else { capitalized(w) })
+            .collect(),
+        Casing::Pascal => words.iter().map(capitalized).collect(),
+        Casing::Snake => words.join("_"),
+        Casing::Constant => words.join("_").to_uppercase(),
+        Casing::Kebab => words.join("-"),
+        Casing::Lower => words.join(" "),
+        Casing::Upper => words.join(" ").to_uppercase(),
+    }
+}
+
+#[derive(Default)]
+struct Output {
+    text: String,
+    glue_next: bool,
+}
+
+impl Output {
+    fn push(&mut self, piece: &str, join: Join) {
+        let (join_left, join_right) = match join {
+            Join::Left => (true, false),
+            Join::Right => (false, true),
+            Join::Both => (true, true),
+            Join::Neither | Join::Auto => (false, false),
+        };
+        if !self.text.is_empty() && !self.glue_next && !join_left {
+            self.text.push(' ');
+        }
+        self.text.push_str(piece);
+        self.glue_next = join_right;
+    }
+}
+
+/// Turns dictated code into source text: casing commands ("camel case user
+/// id" -> `userId`) apply to the words up to the next symbol, command or
+/// pause, and spoken symbols become characters. Whisper's own punctuation and
+/// sentence capitalization are dropped, since in code mode every symbol is
+/// spoken explicitly.
+pub fn apply(text: &str) -> String {
+    let tokens: Vec<Token> = text.split_whitespace().map(Token::parse).collect();
+    let mut out = Output::default();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match phrase_at(&tokens[i..]) {
+            Some((len, Phrase::Symbol(symbol, join))) => {
+                out.push(symbol, join);
+                i += len;
+            }
+            Some((len, Phrase::Casing(casing))) => {
+                i += len;
+                let mut words = Vec::new();
+                while i < tokens.len() && phrase_at(&tokens[i..]).is_none() {
+                    let token = &tokens[i];
+                    words.extend(token.key().split('-').filter(|w| !w.is_empty()).map(str::to_string));
+                    i += 1;
+                    if !token.trail.is_empty() {
+                        break;
+                    }
+                }
+                if !words.is_empty() {
+                    out.push(&apply_casing(&words, casing), Join::Neither);
+                }
+            }
+            None => {
+                let token = &tokens[i];
+                // Keep deliberate capitals like "HTTP" but undo sentence case.
+                let word = if token.word.chars().skip(1).any(|c| c.is_uppercase()) {
+                    token.word.clone()
+                } else {
+                    token.word.to_lowercase()
+                };
+                // Keep symbols such as "$" or "%" from number normalization.
+                let trail = token.trail.trim_end_matches(['.', ',', '?', '!']);
+                let piece = format!("{}{}{}", token.lead, word, trail);
+                if !piece.is_empty() {
+                    out.push(&piece, Join::Neither);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    out.text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn casing_phrases() {
+        assert_eq!(apply("camel case user id"), "userId");
+        assert_eq!(apply("snake case max retries"), "max_retries");
+        assert_eq!(apply("pascal case http client"), "HttpClient");
+        assert_eq!(apply("constant case max size"), "MAX_SIZE");
+        assert_eq!(apply("kebab case main menu"), "main-menu");
+        assert_eq!(apply("all caps todo"), "TODO");
+    }
+
+    #[test]
+    fn casing_ends_at_a_symbol_word() {
+        assert_eq!(apply("snake case max retries equals 5"), "max_retries = 5");
+        assert_eq!(apply("camel case get user open paren close paren"), "getUser()");
+    }
+
+    #[test]
+    fn casing_ends_at_punctuation() {
+        assert_eq!(apply("camel case user id, total"), "userId total");
+        assert_eq!(apply("Snake case first name. Last"), "first_name last");
+    }
+
+    #[test]
+    fn casing_splits_hyphenated_words() {
+        assert_eq!(apply("camel case read-only flag"), "readOnlyFlag");
+    }
+
+    #[test]
+    fn casing_without_words_writes_nothing() {
+        assert_eq!(apply("camel case equals"), "=");
+    }
+
+    #[test]
+    fn brackets_and_arrows() {
+        assert_eq!(apply("print open paren x close paren"), "print(x)");
+        assert_eq!(apply("items open bracket 0 close bracket"), "items[0]");
+        assert_eq!(apply("x arrow y"), "x -> y");
+        assert_eq!(apply("x fat arrow y"), "x => y");
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        assert_eq!(apply("a double equals b"), "a == b");
+        assert_eq!(apply("a triple equals b"), "a === b");
+        assert_eq!(apply("a not equals b"), "a != b");
+        assert_eq!(apply("std double colon io"), "std::io");
+        assert_eq!(apply("screaming snake case max size"), "MAX_SIZE");
+    }
+
+    #[test]
+    fn member_access_is_tight() {
+        assert_eq!(apply("self dot name"), "self.name");
+    }
+
+    #[test]
+    fn keeps_leading_and_trailing_symbols() {
+        assert_eq!(apply("cost is $5."), "cost is $5");
+        assert_eq!(apply("\"quoted\" word"), "\"quoted\" word");
+        assert_eq!(apply("load is 50%"), "load is 50%");
+    }
+
+    #[test]
+    fn drops_sentence_case_but_keeps_deliberate_capitals() {
+        assert_eq!(apply("Call HTTP get"), "call HTTP get");
+    }
+}
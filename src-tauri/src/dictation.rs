@@ -0,0 +1,115 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Where the app is in a dictation cycle. Only one cycle runs at a time: a
+/// new recording can't start while the previous one is still being
+/// transcribed or typed, since both would share the microphone and the
+/// temporary WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Idle,
+    Recording,
+    Processing,
+    Injecting,
+    /// The last cycle failed; the pill shows the error for a moment. A new
+    /// recording may start meanwhile.
+    Error,
+}
+
+struct State {
+    phase: Phase,
+    /// Bumped on every transition, so a delayed hide scheduled by one cycle
+    /// doesn't hide the pill of the next.
+    generation: u64,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { phase: Phase::Idle, generation: 0 });
+
+const SUCCESS_LINGER: Duration = Duration::from_millis(500);
+const CANCELLED_LINGER: Duration = Duration::from_millis(800);
+const ERROR_LINGER: Duration = Duration::from_secs(3);
+
+/// Moves to `to` if the current phase is one of `from`, and shows `pill`.
+/// With `linger`, the pill is hidden again after that long unless another
+/// transition happened first. Returns whether the transition was allowed.
+fn transition(app: &AppHandle, from: &[Phase], to: Phase, pill: &str, linger: Option<Duration>) -> bool {
+    let generation = {
+        let mut state = STATE.lock().unwrap();
+        if !from.contains(&state.phase) {
+            eprintln!("Ignoring dictation transition {:?} -> {:?}", state.phase, to);
+            return false;
+        }
+        state.phase = to;
+        state.generation += 1;
+        // Emit while holding the lock so events from two threads can't
+        // reach the pill out of order.
+        let _ = app.emit_to("main", "pill-state", pill);
+        state.generation
+    };
+
+    match linger {
+        Some(linger) => {
+            let app = app.clone();
+            thread::spawn(move || {
+                thread::sleep(linger);
+                let mut state = STATE.lock().unwrap();
+                if state.generation != generation {
+                    return;
+                }
+                state.phase = Phase::Idle;
+                let _ = app.emit_to("main", "pill-state", "idle");
+                hide_pill(&app);
+            });
+        }
+        None if to == Phase::Idle => hide_pill(app),
+        None => show_pill(app),
+    }
+    true
+}
+
+fn show_pill(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+    }
+}
+
+fn hide_pill(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+}
+
+/// Starts a cycle; rejected while another one is still running.
+pub fn start(app: &AppHandle) -> bool {
+    transition(app, &[Phase::Idle, Phase::Error], Phase::Recording, "listening", None)
+}
+
+/// The recording ended and is being transcribed.
+pub fn stop(app: &AppHandle) -> bool {
+    transition(app, &[Phase::Recording], Phase::Processing, "loading", None)
+}
+
+/// The text is ready and is being typed into the target window.
+pub fn injecting(app: &AppHandle) -> bool {
+    transition(app, &[Phase::Processing], Phase::Injecting, "loading", None)
+}
+
+pub fn succeed(app: &AppHandle) -> bool {
+    transition(app, &[Phase::Processing, Phase::Injecting], Phase::Idle, "success", Some(SUCCESS_LINGER))
+}
+
+pub fn fail(app: &AppHandle) -> bool {
+    transition(app, &[Phase::Recording, Phase::Processing, Phase::Injecting], Phase::Error, "error", Some(ERROR_LINGER))
+}
+
+/// Drops the recording silently, e.g. a tap or a shortcut using the hotkey.
+pub fn discard(app: &AppHandle) -> bool {
+    transition(app, &[Phase::Recording], Phase::Idle, "idle", None)
+}
+
+/// Drops the recording at the user's request and says so.
+pub fn cancel(app: &AppHandle) -> bool {
+    transition(app, &[Phase::Recording], Phase::Idle, "cancelled", Some(CANCELLED_LINGER))
+}
@@ -0,0 +1,254 @@
+use crate::normalization::{self, LanguageTable};
+use crate::text::Token;
+
+/// Filler words and their per-language lists. `fillers` are removed wherever
+/// they appear; `hedges` are real words too ("like", "I mean"), so they are
+/// only removed when Whisper set them off with punctuation.
+struct FillerTable {
+    fillers: &'static [&'static str],
+    hedges: &'static [&'static str],
+    /// Words that legitimately appear twice in a row ("had had").
+    allowed_repeats: &'static [&'static str],
+}
+
+fn filler_table(language: &str) -> FillerTable {
+    match language {
+        "de" => FillerTable {
+            fillers: &["äh", "ähm", "öh", "öhm", "hm", "hmm", "mh"],
+            hedges: &["sozusagen", "quasi", "halt"],
+            allowed_repeats: &["die", "der", "das"],
+        },
+        "fr" => FillerTable {
+            fillers: &["euh", "heu", "hum", "bah"],
+            hedges: &["tu vois", "en fait", "genre", "du coup"],
+            allowed_repeats: &["nous", "vous"],
+        },
+        "es" => FillerTable {
+            fillers: &["eh", "em", "ehm", "mmm"],
+            hedges: &["o sea", "este", "pues"],
+            allowed_repeats: &[],
+        },
+        _ => FillerTable {
+            fillers: &["um", "umm", "uh", "uhm", "er", "erm", "ah", "hmm", "mm", "mhm"],
+            hedges: &["you know", "i mean", "you see", "like", "basically"],
+            allowed_repeats: &["had", "that"],
+        },
+    }
+}
+
+/// Removes `tokens[start..start + len]` and repairs the punctuation and
+/// capitalization around the gap.
+fn remove_span(tokens: &mut Vec<Token>, start: usize, len: usize) {
+    let removed: Vec<Token> = tokens.drain(start..start + len).collect();
+    let first = &removed[0];
+    let last_trail = removed[removed.len() - 1].trail.trim_end_matches(['-', '—', '–']).to_string();
+    let at_sentence_start = start == 0 || tokens[start - 1].ends_sentence();
+
+    if start > 0 {
+        let prev = &mut tokens[start - 1];
+        if last_trail.contains(['.', '?', '!']) && !prev.ends_sentence() {
+            // "I was, um." -> "I was."
+            prev.trail = last_trail.clone();
+        } else if last_trail == "," && prev.trail == "," {
+            // "So, um, we" -> "So we": both commas were around the filler.
+            prev.trail.clear();
+        }
+    }
+
+    if let Some(next) = tokens.get_mut(start) {
+        next.lead = format!("{}{}", first.lead, next.lead);
+        if at_sentence_start && first.starts_uppercase() {
+            next.capitalize();
+        }
+    }
+}
+
+fn phrase_len_at(tokens: &[Token], start: usize, phrase: &[&str]) -> Option<usize> {
+    let candidate = tokens.get(start..start + phrase.len())?;
+    let matches = candidate.iter().zip(phrase).all(|(token, word)| token.key() == *word);
+    // Only the inner tokens must be bare; the last one may carry punctuation.
+    let inner_bare = candidate[..candidate.len() - 1].iter().all(|t| t.trail.is_empty());
+    if matches && inner_bare {
+        Some(phrase.len())
+    } else {
+        None
+    }
+}
+
+fn remove_fillers(tokens: &mut Vec<Token>, table: &FillerTable) {
+    let hedges: Vec<Vec<&str>> = table.hedges.iter().map(|h| h.split_whitespace().collect()).collect();
+    let mut i = 0;
+    while i < tokens.len() {
+        if table.fillers.contains(&tokens[i].key().as_str()) {
+            remove_span(tokens, i, 1);
+            continue;
+        }
+
+        let delimited_before = i == 0 || !tokens[i - 1].trail.is_empty();
+        let hedge = hedges.iter().find_map(|phrase| {
+            let len = phrase_len_at(tokens, i, phrase)?;
+            let end = i + len;
+            let delimited_after = end == tokens.len() || !tokens[end - 1].trail.is_empty();
+            if delimited_before && delimited_after {
+                Some(len)
+            } else {
+                None
+            }
+        });
+        if let Some(len) = hedge {
+            remove_span(tokens, i, len);
+            continue;
+        }
+
+        if tokens[i].is_cut_off() && i + 1 < tokens.len() {
+            remove_span(tokens, i, 1);
+            continue;
+        }
+        i += 1;
+    }
+}
+
+fn is_number(token: &Token, numbers: Option<&LanguageTable>) -> bool {
+    let digits = !token.word.is_empty() && token.word.chars().all(|c| c.is_ascii_digit());
+    digits || numbers.is_some_and(|numbers| numbers.is_number_word(&token.key()))
+}
+
+/// Collapses stutters and restarts such as "I I I think" or
+/// "I want to, I want to go" into a single copy. Numbers are left alone:
+/// "twenty twenty" and "one one two" are meant as said.
+fn collapse_repetitions(tokens: &mut Vec<Token>, table: &FillerTable, numbers: Option<&LanguageTable>) {
+    let mut i = 0;
+    while i < tokens.len() {
+        let repeated = (1..=3).rev().find(|&n| {
+            if i + 2 * n > tokens.len() {
+                return false;
+            }
+            let first = &tokens[i..i + n];
+            let second = &tokens[i + n..i + 2 * n];
+            let same = first.iter().zip(second).all(|(a, b)| a.key() == b.key() && !a.word.is_empty());
+            let allowed = n == 1 && table.allowed_repeats.contains(&first[0].key().as_str());
+            let numeric = first.iter().all(|token| is_number(token, numbers));
+            same && !allowed && !numeric && !first[n - 1].ends_sentence()
+        });
+        match repeated {
+            Some(n) => remove_span(tokens, i, n),
+            None => i += 1,
+        }
+    }
+}
+
+/// Removes filler words, stutters and false starts, then repairs the
+/// punctuation and capitalization the removal left behind.
+pub fn clean(text: &str, language: &str) -> String {
+    let table = filler_table(language);
+    let numbers = normalization::table_for(language);
+    // Line by line so paragraph breaks (e.g. from the LLM pass) survive.
+    text.lines()
+        .map(|line| {
+            let mut tokens: Vec<Token> = line.split_whitespace().map(Token::parse).collect();
+            remove_fillers(&mut tokens, &table);
+            collapse_repetitions(&mut tokens, &table, numbers);
+            tokens
+                .iter()
+                .map(Token::render)
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(text: &str) -> Vec<Token> {
+        text.split_whitespace().map(Token::parse).collect()
+    }
+
+    fn render(tokens: &[Token]) -> String {
+        tokens.iter().map(Token::render).filter(|t| !t.is_empty()).collect::<Vec<_>>().join(" ")
+    }
+
+    fn removed(text: &str, start: usize, len: usize) -> String {
+        let mut tokens = tokens(text);
+        remove_span(&mut tokens, start, len);
+        render(&tokens)
+    }
+
+    #[test]
+    fn remove_span_moves_sentence_end_to_previous_word() {
+        assert_eq!(removed("I was, um.", 2, 1), "I was.");
+        assert_eq!(removed("Really? Um?", 1, 1), "Really?");
+    }
+
+    #[test]
+    fn remove_span_drops_commas_around_the_gap() {
+        assert_eq!(removed("So, um, we left", 1, 1), "So we left");
+        assert_eq!(removed("Well, you know, it works", 1, 2), "Well it works");
+    }
+
+    #[test]
+    fn remove_span_capitalizes_new_sentence_start() {
+        assert_eq!(removed("Um, so it begins", 0, 1), "So it begins");
+        assert_eq!(removed("Done. Uh, next one", 1, 1), "Done. Next one");
+        // Mid-sentence the next word keeps its case.
+        assert_eq!(removed("and uh then", 1, 1), "and then");
+    }
+
+    #[test]
+    fn remove_span_keeps_leading_punctuation() {
+        assert_eq!(removed("he said \"um hello\"", 2, 1), "he said \"hello\"");
+    }
+
+    #[test]
+    fn fillers_are_removed_anywhere() {
+        assert_eq!(clean("Um, I think, uh, we should go.", "en"), "I think we should go.");
+        assert_eq!(clean("We should go, um.", "en"), "We should go.");
+        assert_eq!(clean("Äh, das ist gut.", "de"), "Das ist gut.");
+    }
+
+    #[test]
+    fn hedges_are_removed_only_when_set_off() {
+        assert_eq!(clean("It was, like, huge.", "en"), "It was huge.");
+        assert_eq!(clean("I like it.", "en"), "I like it.");
+        assert_eq!(clean("I mean, it works.", "en"), "It works.");
+    }
+
+    #[test]
+    fn cut_off_words_are_dropped() {
+        assert_eq!(clean("the sto- the store", "en"), "the store");
+    }
+
+    #[test]
+    fn stutters_and_restarts_collapse() {
+        assert_eq!(clean("I I I think so.", "en"), "I think so.");
+        assert_eq!(clean("I want to I want to go.", "en"), "I want to go.");
+        assert_eq!(clean("The the cat.", "en"), "The cat.");
+    }
+
+    #[test]
+    fn allowed_repeats_survive() {
+        assert_eq!(clean("He had had enough.", "en"), "He had had enough.");
+    }
+
+    #[test]
+    fn repeats_across_sentences_survive() {
+        assert_eq!(clean("Go. Go now.", "en"), "Go. Go now.");
+    }
+
+    #[test]
+    fn numbers_are_not_collapsed() {
+        assert_eq!(clean("It was twenty twenty.", "en"), "It was twenty twenty.");
+        assert_eq!(clean("Call one one two.", "en"), "Call one one two.");
+        assert_eq!(clean("Room 4 4 2.", "en"), "Room 4 4 2.");
+        assert_eq!(clean("Code twenty-one twenty-one.", "en"), "Code twenty-one twenty-one.");
+        assert_eq!(clean("Dial 1 1 2.", "de"), "Dial 1 1 2.");
+    }
+
+    #[test]
+    fn paragraph_breaks_survive() {
+        assert_eq!(clean("Um, first.\nUh, second.", "en"), "First.\nSecond.");
+    }
+}
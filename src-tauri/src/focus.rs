@@ -0,0 +1,453 @@
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use tauri::command;
+
+/// The window that had focus when dictation started. Profiles match on it
+/// and the text is injected back into it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActiveWindow {
+    /// Executable or application name without path and extension,
+    /// e.g. "code" or "Slack".
+    pub process_name: Option<String>,
+    pub title: Option<String>,
+    /// X11 window class, e.g. "Slack" or "Google-chrome".
+    #[serde(default)]
+    pub window_class: Option<String>,
+    /// macOS bundle identifier, e.g. "com.tinyspeck.slackmacgap".
+    #[serde(default)]
+    pub bundle_id: Option<String>,
+    #[serde(skip)]
+    pub pid: Option<u32>,
+    /// Native handle used to bring the window back (an HWND on Windows, an
+    /// X11 window id on Linux).
+    #[serde(skip)]
+    pub handle: Option<isize>,
+}
+
+/// What to do with dictated text when the user switched to another window
+/// while it was being transcribed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusChangePolicy {
+    /// Bring the original window back, if possible, and type into it.
+    #[default]
+    Inject,
+    /// Leave the text on the clipboard and tell the user.
+    Clipboard,
+    /// Keep the text until the user pastes it with the pending-paste hotkey.
+    Pending,
+}
+
+impl ActiveWindow {
+    /// Compares window handles where the platform has them, otherwise the
+    /// owning process.
+    pub fn is_same_window(&self, other: &ActiveWindow) -> bool {
+        match (self.handle, other.handle, self.pid, other.pid) {
+            (Some(a), Some(b), _, _) => a == b,
+            (_, _, Some(a), Some(b)) => a == b,
+            _ => self.process_name == other.process_name,
+        }
+    }
+
+    /// One of VWisper's own windows, e.g. after clicking the pill.
+    pub fn is_own_window(&self) -> bool {
+        self.pid == Some(std::process::id())
+    }
+}
+
+/// Strips the directory and ".exe" so "C:\...\Code.exe" and "Code" compare equal.
+pub fn normalize_process_name(name: &str) -> String {
+    let file = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let file = file.strip_suffix(".exe").or_else(|| file.strip_suffix(".EXE")).unwrap_or(file);
+    file.trim().to_string()
+}
+
+/// Finds the window the user is working in and brings it back. Each
+/// platform implements it; code that decides on focus takes it as
+/// `&dyn FocusBackend`, so tests can pass a [`FakeFocus`].
+pub trait FocusBackend: Send + Sync {
+    fn capture(&self) -> Option<ActiveWindow>;
+    /// Brings `window` back to the foreground.
+    fn restore(&self, window: &ActiveWindow) -> Result<(), String>;
+    /// Why windows can't be tracked here, if they can't.
+    fn limitation(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub struct WindowsFocus;
+
+#[cfg(target_os = "windows")]
+impl FocusBackend for WindowsFocus {
+    fn capture(&self) -> Option<ActiveWindow> {
+        use windows::core::PWSTR;
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::{OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION};
+        use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId};
+
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.0 == 0 {
+                return None;
+            }
+
+            let mut title = [0u16; 512];
+            let len = GetWindowTextW(hwnd, &mut title).max(0) as usize;
+            let title = String::from_utf16_lossy(&title[..len]);
+
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+            let process_name = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok().and_then(|process| {
+                let mut path = [0u16; 1024];
+                let mut size = path.len() as u32;
+                let result = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(path.as_mut_ptr()), &mut size);
+                let _ = CloseHandle(process);
+                result.ok().map(|_| normalize_process_name(&String::from_utf16_lossy(&path[..size as usize])))
+            });
+
+            Some(ActiveWindow {
+                process_name,
+                title: Some(title).filter(|t| !t.is_empty()),
+                handle: Some(hwnd.0),
+                pid: Some(pid).filter(|pid| *pid != 0),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn restore(&self, window: &ActiveWindow) -> Result<(), String> {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+
+        let Some(handle) = window.handle else { return Ok(()) };
+        unsafe {
+            if SetForegroundWindow(HWND(handle)).as_bool() {
+                Ok(())
+            } else {
+                Err("SetForegroundWindow was refused".to_string())
+            }
+        }
+    }
+}
+
+/// Reads the frontmost application from `NSWorkspace`, which is fast enough
+/// to run before recording starts. Restoring goes through System Events,
+/// which needs the Accessibility permission the app already asks for to type
+/// text.
+#[cfg(target_os = "macos")]
+pub struct MacFocus;
+
+// objc 0.2's `msg_send!` checks a `cargo-clippy` feature this crate lacks.
+#[cfg(target_os = "macos")]
+#[allow(unexpected_cfgs)]
+impl MacFocus {
+    fn run_script(script: &str) -> Result<String, String> {
+        let output = std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(script)
+            .output()
+            .map_err(|e| format!("Failed to run osascript: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Copies a non-empty `NSString` out, or returns `None`.
+    unsafe fn string(value: *mut objc::runtime::Object) -> Option<String> {
+        use objc::{msg_send, sel, sel_impl};
+
+        if value.is_null() {
+            return None;
+        }
+        let ptr: *const std::os::raw::c_char = msg_send![value, UTF8String];
+        if ptr.is_null() {
+            return None;
+        }
+        let text = std::ffi::CStr::from_ptr(ptr).to_string_lossy().trim().to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// The title of `pid`'s frontmost window. The window server only reports
+    /// titles to apps with the Screen Recording permission, so this is often
+    /// `None`.
+    unsafe fn window_title(pid: i32) -> Option<String> {
+        use objc::runtime::Object;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        #[link(name = "CoreGraphics", kind = "framework")]
+        extern "C" {
+            fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> *mut Object;
+        }
+        const ON_SCREEN_ONLY: u32 = 1 << 0;
+        const EXCLUDE_DESKTOP_ELEMENTS: u32 = 1 << 4;
+
+        let windows = CGWindowListCopyWindowInfo(ON_SCREEN_ONLY | EXCLUDE_DESKTOP_ELEMENTS, 0);
+        if windows.is_null() {
+            return None;
+        }
+        let key = |name: &std::ffi::CStr| -> *mut Object { msg_send![class!(NSString), stringWithUTF8String: name.as_ptr()] };
+        let (owner_pid, layer, name) = (key(c"kCGWindowOwnerPID"), key(c"kCGWindowLayer"), key(c"kCGWindowName"));
+        // Windows are listed front to back; layer 0 skips menus and overlays.
+        let count: usize = msg_send![windows, count];
+        let mut title = None;
+        for index in 0..count {
+            let info: *mut Object = msg_send![windows, objectAtIndex: index];
+            let window_pid: *mut Object = msg_send![info, objectForKey: owner_pid];
+            let window_layer: *mut Object = msg_send![info, objectForKey: layer];
+            let (window_pid, window_layer): (i32, i32) = (msg_send![window_pid, intValue], msg_send![window_layer, intValue]);
+            if window_pid == pid && window_layer == 0 {
+                title = Self::string(msg_send![info, objectForKey: name]);
+                break;
+            }
+        }
+        let () = msg_send![windows, release];
+        title
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(unexpected_cfgs)]
+impl FocusBackend for MacFocus {
+    fn capture(&self) -> Option<ActiveWindow> {
+        use objc::runtime::Object;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let pool: *mut Object = msg_send![class!(NSAutoreleasePool), new];
+            let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let app: *mut Object = msg_send![workspace, frontmostApplication];
+            let window = if app.is_null() {
+                None
+            } else {
+                // The executable name is what System Events and `ps` call the
+                // process; the localized name is only a fallback.
+                let executable: *mut Object = msg_send![app, executableURL];
+                let executable_name = if executable.is_null() { None } else { Self::string(msg_send![executable, lastPathComponent]) };
+                let process_name = executable_name.or_else(|| Self::string(msg_send![app, localizedName]));
+                let pid: i32 = msg_send![app, processIdentifier];
+                process_name.map(|process_name| ActiveWindow {
+                    process_name: Some(process_name),
+                    bundle_id: Self::string(msg_send![app, bundleIdentifier]),
+                    pid: u32::try_from(pid).ok(),
+                    title: Self::window_title(pid),
+                    ..Default::default()
+                })
+            };
+            let () = msg_send![pool, drain];
+            window
+        }
+    }
+
+    fn restore(&self, window: &ActiveWindow) -> Result<(), String> {
+        // The pid picks the right instance when an app runs twice; the name
+        // is a fallback for captures that lacked it.
+        let target = match (window.pid, &window.process_name) {
+            (Some(pid), _) => format!("first application process whose unix id is {}", pid),
+            (None, Some(name)) => format!("application process \"{}\"", name.replace('"', "\\\"")),
+            (None, None) => return Ok(()),
+        };
+        Self::run_script(&format!("tell application \"System Events\" to set frontmost of {} to true", target)).map(|_| ())
+    }
+}
+
+/// Reads EWMH properties from the window manager, which every mainstream
+/// X11 desktop supports.
+#[cfg(target_os = "linux")]
+pub struct X11Focus;
+
+#[cfg(target_os = "linux")]
+impl X11Focus {
+    fn atom(conn: &impl x11rb::connection::Connection, name: &str) -> Result<u32, String> {
+        use x11rb::protocol::xproto::ConnectionExt;
+        Ok(conn.intern_atom(false, name.as_bytes()).map_err(x11_error)?.reply().map_err(x11_error)?.atom)
+    }
+
+    fn property(
+        conn: &impl x11rb::connection::Connection,
+        window: u32,
+        property: u32,
+        kind: u32,
+    ) -> Result<x11rb::protocol::xproto::GetPropertyReply, String> {
+        use x11rb::protocol::xproto::ConnectionExt;
+        conn.get_property(false, window, property, kind, 0, 1024)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)
+    }
+
+    fn try_capture() -> Result<Option<ActiveWindow>, String> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::xproto::AtomEnum;
+
+        let (conn, screen) = x11rb::connect(None).map_err(x11_error)?;
+        let root = conn.setup().roots[screen].root;
+        let active = Self::property(&conn, root, Self::atom(&conn, "_NET_ACTIVE_WINDOW")?, AtomEnum::WINDOW.into())?
+            .value32()
+            .and_then(|mut windows| windows.next())
+            .filter(|window| *window != 0);
+        let Some(window) = active else { return Ok(None) };
+
+        let mut title = Self::property(&conn, window, Self::atom(&conn, "_NET_WM_NAME")?, Self::atom(&conn, "UTF8_STRING")?)?.value;
+        if title.is_empty() {
+            title = Self::property(&conn, window, AtomEnum::WM_NAME.into(), AtomEnum::STRING.into())?.value;
+        }
+        // WM_CLASS holds "instance\0Class\0"; the class names the application.
+        let class = Self::property(&conn, window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING.into())?.value;
+        let class = class
+            .split(|b| *b == 0)
+            .nth(1)
+            .map(|class| String::from_utf8_lossy(class).to_string())
+            .filter(|class| !class.is_empty());
+        let pid = Self::property(&conn, window, Self::atom(&conn, "_NET_WM_PID")?, AtomEnum::CARDINAL.into())?
+            .value32()
+            .and_then(|mut pids| pids.next());
+        // Profiles match executable names, which the class often isn't
+        // ("code" vs "Code", "google-chrome" vs "Google-chrome").
+        let process_name = pid
+            .and_then(|pid| std::fs::read_link(format!("/proc/{}/exe", pid)).ok())
+            .map(|exe| normalize_process_name(&exe.to_string_lossy()))
+            .or_else(|| class.clone());
+
+        Ok(Some(ActiveWindow {
+            process_name,
+            title: Some(String::from_utf8_lossy(&title).to_string()).filter(|t| !t.is_empty()),
+            window_class: class,
+            pid,
+            handle: Some(window as isize),
+            ..Default::default()
+        }))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn x11_error(e: impl std::fmt::Display) -> String {
+    format!("X11 error: {}", e)
+}
+
+#[cfg(target_os = "linux")]
+impl FocusBackend for X11Focus {
+    fn capture(&self) -> Option<ActiveWindow> {
+        Self::try_capture().unwrap_or_else(|e| {
+            eprintln!("Failed to capture the active window: {}", e);
+            None
+        })
+    }
+
+    fn restore(&self, window: &ActiveWindow) -> Result<(), String> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::xproto::{ClientMessageEvent, ConnectionExt, EventMask};
+
+        let Some(handle) = window.handle else { return Ok(()) };
+        let (conn, screen) = x11rb::connect(None).map_err(x11_error)?;
+        let root = conn.setup().roots[screen].root;
+        // Source 2 marks the request as coming from a pager-like tool, which
+        // window managers honour instead of just flashing the taskbar entry.
+        let event = ClientMessageEvent::new(32, handle as u32, Self::atom(&conn, "_NET_ACTIVE_WINDOW")?, [2, 0, 0, 0, 0]);
+        conn.send_event(false, root, EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY, event)
+            .map_err(x11_error)?;
+        conn.flush().map_err(x11_error)
+    }
+}
+
+/// Platforms, and Wayland sessions, without a way to query or change the
+/// focused window.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub struct NoFocus {
+    pub limitation: &'static str,
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+impl FocusBackend for NoFocus {
+    fn capture(&self) -> Option<ActiveWindow> {
+        None
+    }
+
+    fn restore(&self, _window: &ActiveWindow) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn limitation(&self) -> Option<&'static str> {
+        Some(self.limitation)
+    }
+}
+
+#[cfg(target_os = "linux")]
+const WAYLAND_LIMITATION: &str = "Wayland doesn't let applications see or change the focused window. Text is typed into whichever window has focus when dictation ends, and per-app profiles and history can't tell which app it was.";
+
+fn platform_backend() -> Box<dyn FocusBackend> {
+    #[cfg(target_os = "windows")]
+    let backend: Box<dyn FocusBackend> = Box::new(WindowsFocus);
+    #[cfg(target_os = "macos")]
+    let backend: Box<dyn FocusBackend> = Box::new(MacFocus);
+    #[cfg(target_os = "linux")]
+    let backend: Box<dyn FocusBackend> = match crate::platform::linux::LinuxSession::detect() {
+        crate::platform::linux::LinuxSession::X11 => Box::new(X11Focus),
+        crate::platform::linux::LinuxSession::Wayland => Box::new(NoFocus { limitation: WAYLAND_LIMITATION }),
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    let backend: Box<dyn FocusBackend> = Box::new(NoFocus { limitation: "Focus tracking is not supported on this platform." });
+    backend
+}
+
+pub fn backend() -> &'static dyn FocusBackend {
+    static BACKEND: OnceLock<Box<dyn FocusBackend>> = OnceLock::new();
+    BACKEND.get_or_init(platform_backend).as_ref()
+}
+
+pub fn capture_active_window() -> Option<ActiveWindow> {
+    backend().capture()
+}
+
+/// A backend with a fixed focused window that records restore calls.
+#[cfg(test)]
+#[derive(Default)]
+pub struct FakeFocus {
+    pub current: Option<ActiveWindow>,
+    pub limitation: Option<&'static str>,
+    pub restored: std::sync::Mutex<Vec<ActiveWindow>>,
+}
+
+#[cfg(test)]
+impl FakeFocus {
+    pub fn focused(current: ActiveWindow) -> Self {
+        Self { current: Some(current), ..Default::default() }
+    }
+
+    pub fn restored_handles(&self) -> Vec<Option<isize>> {
+        self.restored.lock().unwrap().iter().map(|w| w.handle).collect()
+    }
+}
+
+#[cfg(test)]
+impl FocusBackend for FakeFocus {
+    fn capture(&self) -> Option<ActiveWindow> {
+        self.current.clone()
+    }
+
+    fn restore(&self, window: &ActiveWindow) -> Result<(), String> {
+        self.restored.lock().unwrap().push(window.clone());
+        Ok(())
+    }
+
+    fn limitation(&self) -> Option<&'static str> {
+        self.limitation
+    }
+}
+
+/// What the settings UI should tell the user about focus tracking.
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusSupport {
+    pub supported: bool,
+    pub limitation: Option<String>,
+}
+
+#[command]
+pub fn get_focus_support() -> FocusSupport {
+    let limitation = backend().limitation();
+    FocusSupport {
+        supported: limitation.is_none(),
+        limitation: limitation.map(str::to_string),
+    }
+}
@@ -0,0 +1,409 @@
+use serde::{Deserialize, Serialize};
+use tauri::command;
+use crate::settings;
+
+/// How a command's output is glued to its neighbours. `Auto` infers it from
+/// the output: punctuation sticks to the previous word, whitespace and
+/// opening brackets stick to the next one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Join {
+    #[default]
+    Auto,
+    Left,
+    Right,
+    Both,
+    Neither,
+}
+
+/// A spoken phrase and the text it is replaced with, e.g. "new line" -> "\n".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormattingCommand {
+    pub phrase: String,
+    pub output: String,
+    #[serde(default)]
+    pub join: Join,
+}
+
+impl FormattingCommand {
+    fn new(phrase: &str, output: &str) -> Self {
+        Self { phrase: phrase.to_string(), output: output.to_string(), join: Join::Auto }
+    }
+
+    fn with_join(phrase: &str, output: &str, join: Join) -> Self {
+        Self { phrase: phrase.to_string(), output: output.to_string(), join }
+    }
+
+    /// Returns (no space before, no space after).
+    fn joins(&self) -> (bool, bool) {
+        match self.join {
+            Join::Left => (true, false),
+            Join::Right => (false, true),
+            Join::Both => (true, true),
+            Join::Neither => (false, false),
+            Join::Auto => {
+                let left = self
+                    .output
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_whitespace() || ",.;:!?)]}%".contains(c));
+                let right = self
+                    .output
+                    .chars()
+                    .last()
+                    .is_some_and(|c| c.is_whitespace() || "([{".contains(c));
+                (left, right)
+            }
+        }
+    }
+
+    fn is_punctuation(&self) -> bool {
+        self.output.starts_with(|c: char| ",.;:!?".contains(c))
+    }
+
+    fn ends_sentence(&self) -> bool {
+        self.output.contains('\n') || self.output.trim_end().ends_with(['.', '?', '!'])
+    }
+}
+
+/// Word that makes the following command be typed literally, e.g.
+/// "literal comma" -> "comma".
+pub fn escape_word(language: &str) -> &'static str {
+    match language {
+        "de" => "wörtlich",
+        "fr" => "littéralement",
+        _ => "literal",
+    }
+}
+
+pub fn default_commands(language: &str) -> Vec<FormattingCommand> {
+    let table: &[(&str, &str)] = match language {
+        "de" => &[
+            ("neue Zeile", "\n"),
+            ("neuer Absatz", "\n\n"),
+            ("Komma", ","),
+            ("Punkt", "."),
+            ("Fragezeichen", "?"),
+            ("Ausrufezeichen", "!"),
+            ("Doppelpunkt", ":"),
+            ("Semikolon", ";"),
+            ("Tabulator", "\t"),
+            ("Aufzählungspunkt", "\n- "),
+        ],
+        "fr" => &[
+            ("à la ligne", "\n"),
+            ("nouvelle ligne", "\n"),
+            ("nouveau paragraphe", "\n\n"),
+            ("virgule", ","),
+            ("point final", "."),
+            ("point d'interrogation", "?"),
+            ("point d'exclamation", "!"),
+            ("deux points", ":"),
+            ("point virgule", ";"),
+            ("tabulation", "\t"),
+            ("puce", "\n- "),
+        ],
+        "es" => &[
+            ("nueva línea", "\n"),
+            ("nuevo párrafo", "\n\n"),
+            ("coma", ","),
+            ("punto y coma", ";"),
+            ("punto", "."),
+            ("signo de interrogación", "?"),
+            ("signo de exclamación", "!"),
+            ("dos puntos", ":"),
+            ("tabulador", "\t"),
+            ("viñeta", "\n- "),
+        ],
+        _ => &[
+            ("new line", "\n"),
+            ("newline", "\n"),
+            ("new paragraph", "\n\n"),
+            ("comma", ","),
+            ("period", "."),
+            ("full stop", "."),
+            ("question mark", "?"),
+            ("exclamation mark", "!"),
+            ("exclamation point", "!"),
+            ("colon", ":"),
+            ("semicolon", ";"),
+            ("dot dot dot", "..."),
+            ("tab key", "\t"),
+            ("bullet point", "\n- "),
+        ],
+    };
+    let mut commands: Vec<FormattingCommand> = table
+        .iter()
+        .map(|(phrase, output)| FormattingCommand::new(phrase, output))
+        .collect();
+    if !matches!(language, "de" | "fr" | "es") {
+        commands.push(FormattingCommand::with_join("open quote", "\"", Join::Right));
+        commands.push(FormattingCommand::with_join("close quote", "\"", Join::Left));
+    }
+    commands
+}
+
+/// The user's table for `language` if they edited one, the defaults otherwise.
+pub fn commands_for(settings: &settings::Settings, language: &str) -> Vec<FormattingCommand> {
+    settings
+        .formatting_commands
+        .get(language)
+        .cloned()
+        .unwrap_or_else(|| default_commands(language))
+}
+
+fn normalize(token: &str) -> String {
+    token
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn has_quote(token: &str) -> bool {
+    token.contains(['"', '“', '”'])
+}
+
+fn quote_state_after(token: &str, mut in_quote: bool) -> bool {
+    for c in token.chars() {
+        match c {
+            '"' => in_quote = !in_quote,
+            '“' => in_quote = true,
+            '”' => in_quote = false,
+            _ => {}
+        }
+    }
+    in_quote
+}
+
+#[derive(Default)]
+struct Output {
+    text: String,
+    glue_next: bool,
+    capitalize_next: bool,
+}
+
+impl Output {
+    fn push_word(&mut self, word: &str) {
+        if !self.text.is_empty() && !self.glue_next {
+            self.text.push(' ');
+        }
+        if self.capitalize_next {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                self.text.extend(first.to_uppercase());
+                self.text.push_str(chars.as_str());
+            }
+        } else {
+            self.text.push_str(word);
+        }
+        self.glue_next = false;
+        self.capitalize_next = false;
+    }
+
+    fn push_command(&mut self, command: &FormattingCommand) {
+        // Whisper punctuates the pause around a spoken command itself. A
+        // spoken punctuation mark replaces whatever it guessed; other commands
+        // only drop the stray comma.
+        if command.is_punctuation() {
+            let trimmed = self.text.trim_end_matches([',', '.', ';', ':', '!', '?']).len();
+            self.text.truncate(trimmed);
+        } else if self.text.ends_with(',') {
+            self.text.pop();
+        }
+
+        let (join_left, join_right) = command.joins();
+        if !self.text.is_empty() && !self.glue_next && !join_left {
+            self.text.push(' ');
+        }
+        self.text.push_str(&command.output);
+        self.glue_next = join_right;
+        self.capitalize_next = command.ends_sentence();
+    }
+}
+
+fn match_command<'a>(tokens: &[&str], commands: &'a [(Vec<String>, &FormattingCommand)]) -> Option<(usize, &'a FormattingCommand)> {
+    commands.iter().find_map(|(words, command)| {
+        let candidate = tokens.get(..words.len())?;
+        let matches = candidate
+            .iter()
+            .zip(words)
+            .all(|(token, word)| !has_quote(token) && normalize(token) == *word);
+        if matches {
+            Some((words.len(), *command))
+        } else {
+            None
+        }
+    })
+}
+
+/// Replaces spoken formatting commands in `text`. Anything between quotes is
+/// left alone, and a command preceded by `escape` is typed as words.
+pub fn apply(text: &str, commands: &[FormattingCommand], escape: &str) -> String {
+    let mut table: Vec<(Vec<String>, &FormattingCommand)> = commands
+        .iter()
+        .map(|command| (command.phrase.split_whitespace().map(normalize).collect::<Vec<_>>(), command))
+        .filter(|(words, _)| !words.is_empty() && words.iter().all(|w| !w.is_empty()))
+        .collect();
+    // Longest phrases first so "new paragraph" wins over "new".
+    table.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut out = Output::default();
+    let mut in_quote = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i];
+        if in_quote || has_quote(token) {
+            out.push_word(token);
+            in_quote = quote_state_after(token, in_quote);
+            i += 1;
+            continue;
+        }
+        if normalize(token) == escape {
+            if let Some((len, _)) = match_command(&tokens[i + 1..], &table) {
+                for word in &tokens[i + 1..i + 1 + len] {
+                    out.push_word(word);
+                }
+                i += 1 + len;
+                continue;
+            }
+        }
+        if let Some((len, command)) = match_command(&tokens[i..], &table) {
+            out.push_command(command);
+            i += len;
+            continue;
+        }
+        out.push_word(token);
+        i += 1;
+    }
+
+    out.text
+}
+
+#[command]
+pub fn get_formatting_commands(language: String) -> Result<Vec<FormattingCommand>, String> {
+    let settings = settings::get_settings()?;
+    Ok(commands_for(&settings, &language))
+}
+
+#[command]
+pub fn save_formatting_commands(language: String, commands: Vec<FormattingCommand>) -> Result<(), String> {
+    settings::update_settings(|settings| {
+        settings.formatting_commands.insert(language, commands);
+    })
+}
+
+#[command]
+pub fn reset_formatting_commands(language: String) -> Result<(), String> {
+    settings::update_settings(|settings| {
+        settings.formatting_commands.remove(&language);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english(text: &str) -> String {
+        apply(text, &default_commands("en"), escape_word("en"))
+    }
+
+    fn with(command: FormattingCommand, text: &str) -> String {
+        apply(text, &[command], "literal")
+    }
+
+    #[test]
+    fn auto_join_sticks_punctuation_left_and_whitespace_both_ways() {
+        assert_eq!(english("hello comma world"), "hello, world");
+        assert_eq!(english("first line new line second line"), "first line\nSecond line");
+        assert_eq!(english("items bullet point milk"), "items\n- Milk");
+    }
+
+    #[test]
+    fn explicit_joins() {
+        let cmd = |join| FormattingCommand::with_join("dash", "-", join);
+        assert_eq!(with(cmd(Join::Left), "a dash b"), "a- b");
+        assert_eq!(with(cmd(Join::Right), "a dash b"), "a -b");
+        assert_eq!(with(cmd(Join::Both), "a dash b"), "a-b");
+        assert_eq!(with(cmd(Join::Neither), "a dash b"), "a - b");
+        assert_eq!(with(cmd(Join::Auto), "a dash b"), "a - b");
+    }
+
+    #[test]
+    fn quote_commands_wrap_the_words_between_them() {
+        assert_eq!(english("he said open quote hi close quote"), "he said \"hi\"");
+    }
+
+    #[test]
+    fn spoken_punctuation_replaces_whispers_guess() {
+        assert_eq!(english("really, question mark"), "really?");
+        assert_eq!(english("done. period"), "done.");
+    }
+
+    #[test]
+    fn sentence_ending_commands_capitalize_the_next_word() {
+        assert_eq!(english("stop period next one"), "stop. Next one");
+        assert_eq!(english("what question mark nothing"), "what? Nothing");
+    }
+
+    #[test]
+    fn commands_inside_quoted_text_are_kept() {
+        assert_eq!(english("type \"new line\" here"), "type \"new line\" here");
+        assert_eq!(english("say “a comma b” now comma ok"), "say “a comma b” now, ok");
+    }
+
+    #[test]
+    fn escape_word_types_the_command_literally() {
+        assert_eq!(english("the word literal comma is short"), "the word comma is short");
+        assert_eq!(english("literal new paragraph"), "new paragraph");
+        // Not followed by a command: the escape word stays.
+        assert_eq!(english("literal meaning"), "literal meaning");
+    }
+
+    #[test]
+    fn matching_ignores_case_and_surrounding_punctuation() {
+        assert_eq!(english("Hello, New Line. world"), "Hello\nWorld");
+    }
+
+    #[test]
+    fn per_language_defaults() {
+        let run = |language: &str, text: &str| apply(text, &default_commands(language), escape_word(language));
+        assert_eq!(run("en", "yes comma no"), "yes, no");
+        assert_eq!(run("de", "ja Komma nein Punkt"), "ja, nein.");
+        assert_eq!(run("de", "wörtlich Komma"), "Komma");
+        assert_eq!(run("fr", "oui virgule non point d'interrogation"), "oui, non?");
+        assert_eq!(run("fr", "littéralement virgule"), "virgule");
+        assert_eq!(run("es", "sí coma no punto y coma"), "sí, no;");
+        assert_eq!(run("es", "hola nueva línea adiós"), "hola\nAdiós");
+    }
+
+    #[test]
+    fn unknown_languages_fall_back_to_english() {
+        assert_eq!(default_commands("xx"), default_commands("en"));
+        assert_eq!(escape_word("xx"), "literal");
+    }
+
+    #[test]
+    fn user_edited_table_round_trips_through_settings() {
+        let mut commands = default_commands("en");
+        commands.retain(|c| c.phrase != "comma");
+        commands.push(FormattingCommand::with_join("arrow", " -> ", Join::Both));
+
+        let mut settings = settings::Settings::default();
+        settings.formatting_commands.insert("en".to_string(), commands.clone());
+        let json = serde_json::to_string(&settings.formatting_commands).unwrap();
+        settings.formatting_commands = serde_json::from_str(&json).unwrap();
+
+        let loaded = commands_for(&settings, "en");
+        assert_eq!(loaded, commands);
+        assert_eq!(apply("a arrow b comma c", &loaded, "literal"), "a -> b comma c");
+        // Other languages keep their defaults.
+        assert_eq!(commands_for(&settings, "de"), default_commands("de"));
+    }
+
+    #[test]
+    fn join_defaults_to_auto_when_missing_from_json() {
+        let command: FormattingCommand = serde_json::from_str(r#"{"phrase":"smiley","output":":)"}"#).unwrap();
+        assert_eq!(command.join, Join::Auto);
+    }
+}
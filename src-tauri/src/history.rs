@@ -14,6 +14,28 @@ pub struct TranscriptionEntry {
     pub hold_time_ms: Option<u64>,
     pub status: String,
     pub wav_path: Option<String>,
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(default)]
+    pub source_language: Option<String>,
+    /// Transcript as returned by Whisper, set when post-processing changed it.
+    #[serde(default)]
+    pub raw_text: Option<String>,
+    /// Process name of the application the text was dictated into.
+    #[serde(default)]
+    pub app_name: Option<String>,
+    /// Title of the window dictated into.
+    #[serde(default)]
+    pub window_title: Option<String>,
+    /// Name of the per-application profile that was applied, if any.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Action of the hotkey used: "dictate", "cleanup", "translate" or "command".
+    #[serde(default)]
+    pub action: Option<String>,
+    /// When the text was taken back with "undo last dictation".
+    #[serde(default)]
+    pub undone_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize)]
@@ -49,8 +71,21 @@ impl History {
     pub fn add_entry(&self, entry: TranscriptionEntry) {
         let mut entries = self.entries.lock().unwrap();
         entries.push(entry);
+        Self::save(&entries);
+    }
+
+    /// Changes the entry with the given id, if it still exists.
+    pub fn update_entry(&self, id: &str, update: impl FnOnce(&mut TranscriptionEntry)) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) {
+            update(entry);
+            Self::save(&entries);
+        }
+    }
+
+    fn save(entries: &[TranscriptionEntry]) {
         let data = HistoryData {
-            entries: entries.clone(),
+            entries: entries.to_vec(),
         };
         let _ = fs::write(
             history_path(),
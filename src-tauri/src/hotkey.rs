@@ -0,0 +1,543 @@
+use std::fmt;
+use std::sync::RwLock;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use crate::actions::HotkeyBinding;
+use crate::keystate::RecordingMode;
+use crate::settings::{self, Settings};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Either,
+    Left,
+    Right,
+}
+
+/// A physical key or mouse button, independent of the input backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Ctrl(Side),
+    Alt(Side),
+    Shift(Side),
+    Meta(Side),
+    /// Letters (lowercase), digits and punctuation.
+    Char(char),
+    F(u8),
+    Space,
+    Enter,
+    Tab,
+    Escape,
+    Backspace,
+    CapsLock,
+    Insert,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+    /// 3 is the middle button, 4 and 5 the side buttons.
+    Mouse(u8),
+}
+
+const NAMED_KEYS: &[(&str, Key)] = &[
+    ("space", Key::Space),
+    ("enter", Key::Enter),
+    ("return", Key::Enter),
+    ("tab", Key::Tab),
+    ("escape", Key::Escape),
+    ("esc", Key::Escape),
+    ("backspace", Key::Backspace),
+    ("capslock", Key::CapsLock),
+    ("insert", Key::Insert),
+    ("delete", Key::Delete),
+    ("del", Key::Delete),
+    ("home", Key::Home),
+    ("end", Key::End),
+    ("pageup", Key::PageUp),
+    ("pagedown", Key::PageDown),
+    ("up", Key::Up),
+    ("uparrow", Key::Up),
+    ("down", Key::Down),
+    ("downarrow", Key::Down),
+    ("left", Key::Left),
+    ("leftarrow", Key::Left),
+    ("right", Key::Right),
+    ("rightarrow", Key::Right),
+    ("grave", Key::Char('`')),
+    ("backquote", Key::Char('`')),
+    ("minus", Key::Char('-')),
+    ("equal", Key::Char('=')),
+    ("leftbracket", Key::Char('[')),
+    ("leftbrace", Key::Char('[')),
+    ("rightbracket", Key::Char(']')),
+    ("rightbrace", Key::Char(']')),
+    ("backslash", Key::Char('\\')),
+    ("semicolon", Key::Char(';')),
+    ("apostrophe", Key::Char('\'')),
+    ("quote", Key::Char('\'')),
+    ("comma", Key::Char(',')),
+    ("dot", Key::Char('.')),
+    ("period", Key::Char('.')),
+    ("slash", Key::Char('/')),
+];
+
+fn modifier(name: &str) -> Option<fn(Side) -> Key> {
+    match name {
+        "ctrl" | "control" => Some(Key::Ctrl),
+        "alt" | "option" | "opt" => Some(Key::Alt),
+        "shift" => Some(Key::Shift),
+        "meta" | "cmd" | "command" | "super" | "win" => Some(Key::Meta),
+        _ => None,
+    }
+}
+
+impl Key {
+    /// Parses a single key name such as "RCtrl", "Space", "F9" or "Mouse4".
+    /// Names are case-insensitive.
+    pub fn parse(name: &str) -> Result<Key, String> {
+        let lower = name.trim().to_lowercase();
+        if lower.is_empty() {
+            return Err("Empty key name".to_string());
+        }
+
+        // "LeftCtrl", "RCtrl", "Ctrl"...
+        for (prefix, side) in [("left", Side::Left), ("right", Side::Right), ("l", Side::Left), ("r", Side::Right), ("", Side::Either)] {
+            if let Some(key) = lower.strip_prefix(prefix).and_then(modifier) {
+                return Ok(key(side));
+            }
+        }
+
+        if let Some((_, key)) = NAMED_KEYS.iter().find(|(n, _)| *n == lower) {
+            return Ok(*key);
+        }
+
+        if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            return if (1..=24).contains(&n) { Ok(Key::F(n)) } else { Err(format!("Unknown key '{}'", name.trim())) };
+        }
+
+        if let Some(n) = lower.strip_prefix("mouse").and_then(|n| n.parse::<u8>().ok()) {
+            return match n {
+                1 | 2 => Err("The left and right mouse buttons cannot be used as a hotkey".to_string()),
+                3..=5 => Ok(Key::Mouse(n)),
+                _ => Err(format!("Unknown mouse button '{}'", name.trim())),
+            };
+        }
+
+        let mut chars = lower.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphanumeric() || "`-=[]\\;',./".contains(c) => Ok(Key::Char(c)),
+            _ => Err(format!("Unknown key '{}'", name.trim())),
+        }
+    }
+
+    pub fn is_modifier(&self) -> bool {
+        matches!(self, Key::Ctrl(_) | Key::Alt(_) | Key::Shift(_) | Key::Meta(_))
+    }
+
+    /// Whether the pressed key `other` satisfies this hotkey key; a plain
+    /// "Ctrl" accepts either Control key.
+    pub fn matches(&self, other: &Key) -> bool {
+        match (self, other) {
+            (Key::Ctrl(Side::Either), Key::Ctrl(_))
+            | (Key::Alt(Side::Either), Key::Alt(_))
+            | (Key::Shift(Side::Either), Key::Shift(_))
+            | (Key::Meta(Side::Either), Key::Meta(_)) => true,
+            _ => self == other,
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifier = |f: &mut fmt::Formatter<'_>, side: &Side, name: &str| match side {
+            Side::Either => write!(f, "{}", name),
+            Side::Left => write!(f, "L{}", name),
+            Side::Right => write!(f, "R{}", name),
+        };
+        match self {
+            Key::Ctrl(side) => modifier(f, side, "Ctrl"),
+            Key::Alt(side) => modifier(f, side, "Alt"),
+            Key::Shift(side) => modifier(f, side, "Shift"),
+            Key::Meta(side) => modifier(f, side, "Meta"),
+            Key::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            Key::F(n) => write!(f, "F{}", n),
+            Key::Mouse(n) => write!(f, "Mouse{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// A push-to-talk hotkey: one key, or modifiers plus one key, all held at
+/// once. Written as e.g. "RCtrl", "Ctrl+Alt+Space" or "Mouse4".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotkey {
+    keys: Vec<Key>,
+}
+
+impl Hotkey {
+    pub fn parse(text: &str) -> Result<Hotkey, String> {
+        if text.trim().is_empty() {
+            return Err("Hotkey cannot be empty".to_string());
+        }
+        let mut keys = text.split('+').map(Key::parse).collect::<Result<Vec<_>, _>>()?;
+
+        if keys.iter().filter(|k| !k.is_modifier()).count() > 1 {
+            return Err("A hotkey can combine modifiers with only one other key".to_string());
+        }
+        for (i, key) in keys.iter().enumerate() {
+            if keys[i + 1..].iter().any(|other| key.matches(other) || other.matches(key)) {
+                return Err(format!("'{}' appears more than once in the hotkey", key));
+            }
+        }
+        // Modifiers first, so "Space+Ctrl" and "Ctrl+Space" are the same.
+        keys.sort_by_key(|k| !k.is_modifier());
+        Ok(Hotkey { keys })
+    }
+
+    /// The platform's historical default: the right Control key, or either
+    /// Control key on macOS, whose keyboards mostly lack a right one.
+    pub fn platform_default() -> Hotkey {
+        let key = if cfg!(target_os = "macos") { Key::Ctrl(Side::Either) } else { Key::Ctrl(Side::Right) };
+        Hotkey { keys: vec![key] }
+    }
+
+    pub fn from_settings(settings: &Settings) -> Hotkey {
+        match settings.hotkey.as_deref().filter(|h| !h.trim().is_empty()) {
+            Some(text) => Hotkey::parse(text).unwrap_or_else(|e| {
+                eprintln!("Invalid hotkey '{}', using the default: {}", text, e);
+                Hotkey::platform_default()
+            }),
+            None => Hotkey::platform_default(),
+        }
+    }
+
+    /// Whether every key of the hotkey is among the currently `pressed` keys.
+    pub fn is_pressed(&self, pressed: &[Key]) -> bool {
+        self.keys.iter().all(|key| pressed.iter().any(|p| key.matches(p)))
+    }
+
+    /// Whether `pressed` includes keys outside the hotkey, i.e. the hotkey
+    /// is being used as part of another shortcut such as Ctrl+C.
+    pub fn has_other_keys(&self, pressed: &[Key]) -> bool {
+        pressed.iter().any(|p| !self.keys.iter().any(|key| key.matches(p)))
+    }
+
+    /// Whether one hotkey can't be pressed without also pressing the other,
+    /// e.g. "Ctrl" and "Ctrl+Space": whichever completes first would start
+    /// recording and the other could never be used.
+    pub fn overlaps(&self, other: &Hotkey) -> bool {
+        let covers = |a: &Hotkey, b: &Hotkey| a.keys.iter().all(|key| b.keys.iter().any(|k| key.matches(k) || k.matches(key)));
+        covers(self, other) || covers(other, self)
+    }
+}
+
+/// Rejects a set of hotkeys in which one overlaps another.
+pub fn check_overlaps(hotkeys: &[Hotkey]) -> Result<(), String> {
+    for (i, hotkey) in hotkeys.iter().enumerate() {
+        if let Some(other) = hotkeys[i + 1..].iter().find(|other| hotkey.overlaps(other)) {
+            return Err(format!("'{}' and '{}' overlap; a hotkey can't be part of another", hotkey, other));
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self.keys.iter().map(Key::to_string).collect();
+        write!(f, "{}", names.join("+"))
+    }
+}
+
+/// Holds shorter than this are taps, not dictation.
+pub const DEFAULT_MIN_HOLD_MS: u64 = 250;
+
+/// The hotkey settings the key monitors need, cached so they don't read
+/// settings.json on every key event.
+#[derive(Debug, Clone)]
+pub struct TriggerConfig {
+    /// The main hotkey, bound to dictation, followed by the extra bindings.
+    pub bindings: Vec<(Hotkey, HotkeyBinding)>,
+    pub mode: RecordingMode,
+    pub min_hold: Duration,
+    /// Aborts the recording; `None` when disabled.
+    pub cancel_key: Option<Key>,
+    /// Types the text held back after a focus change; `None` when disabled.
+    pub pending_paste: Option<Hotkey>,
+    /// Takes back the last dictation; `None` when disabled.
+    pub undo: Option<Hotkey>,
+}
+
+impl TriggerConfig {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            bindings: bindings_from_settings(settings),
+            mode: settings.recording_mode,
+            min_hold: Duration::from_millis(settings.min_hold_ms.unwrap_or(DEFAULT_MIN_HOLD_MS)),
+            cancel_key: match settings.cancel_key.as_deref().map(str::trim) {
+                None => Some(Key::Escape),
+                Some("") => None,
+                Some(name) => Key::parse(name)
+                    .map_err(|e| eprintln!("Invalid cancel key '{}', using Escape: {}", name, e))
+                    .ok()
+                    .or(Some(Key::Escape)),
+            },
+            pending_paste: extra_hotkey_from_settings(settings, settings.pending_paste_hotkey.as_deref(), "pending-paste"),
+            undo: extra_hotkey_from_settings(settings, settings.undo_hotkey.as_deref(), "undo"),
+        }
+    }
+}
+
+/// Skips extra bindings that don't parse or that overlap an earlier one,
+/// so a hand-edited settings file can't disable the main hotkey.
+fn bindings_from_settings(settings: &Settings) -> Vec<(Hotkey, HotkeyBinding)> {
+    let main = Hotkey::from_settings(settings);
+    let mut bindings = vec![(main.clone(), HotkeyBinding { hotkey: main.to_string(), ..Default::default() })];
+    for binding in &settings.hotkey_bindings {
+        match Hotkey::parse(&binding.hotkey) {
+            Ok(hotkey) if bindings.iter().any(|(other, _)| hotkey.overlaps(other)) => {
+                eprintln!("Ignoring hotkey '{}' for {}: it overlaps another hotkey", hotkey, binding.action.as_str());
+            }
+            Ok(hotkey) => bindings.push((hotkey, binding.clone())),
+            Err(e) => eprintln!("Ignoring invalid hotkey '{}': {}", binding.hotkey, e),
+        }
+    }
+    bindings
+}
+
+/// A hotkey outside dictation, such as the pending-paste one; `None` when
+/// unset, invalid or overlapping a dictation hotkey.
+fn extra_hotkey_from_settings(settings: &Settings, text: Option<&str>, what: &str) -> Option<Hotkey> {
+    let text = text.filter(|h| !h.trim().is_empty())?;
+    match Hotkey::parse(text) {
+        Ok(hotkey) if bindings_from_settings(settings).iter().any(|(other, _)| hotkey.overlaps(other)) => {
+            eprintln!("Ignoring {} hotkey '{}': it overlaps a dictation hotkey", what, hotkey);
+            None
+        }
+        Ok(hotkey) => Some(hotkey),
+        Err(e) => {
+            eprintln!("Ignoring invalid {} hotkey '{}': {}", what, text, e);
+            None
+        }
+    }
+}
+
+static ACTIVE: Lazy<RwLock<TriggerConfig>> = Lazy::new(|| {
+    let settings = settings::get_settings().unwrap_or_default();
+    RwLock::new(TriggerConfig::from_settings(&settings))
+});
+
+/// What the key monitors listen for. They read it on every event, so a
+/// change takes effect without a restart.
+pub fn active() -> TriggerConfig {
+    ACTIVE.read().unwrap().clone()
+}
+
+/// Picks up hotkey settings that were just saved.
+pub fn reload() {
+    let settings = settings::get_settings().unwrap_or_default();
+    *ACTIVE.write().unwrap() = TriggerConfig::from_settings(&settings);
+}
+
+/// Maps the `Debug` name of a key from rdev ("ControlRight", "KeyA", "Num1")
+/// or evdev ("KEY_RIGHTCTRL", "BTN_SIDE") to a `Key`.
+pub fn from_native_name(name: &str) -> Option<Key> {
+    match name {
+        // Left-hand keys that rdev names without a side.
+        "Alt" => return Some(Key::Alt(Side::Left)),
+        "AltGr" => return Some(Key::Alt(Side::Right)),
+        "Command" => return Some(Key::Meta(Side::Left)),
+        "RCommand" => return Some(Key::Meta(Side::Right)),
+        "BTN_MIDDLE" => return Some(Key::Mouse(3)),
+        "BTN_SIDE" => return Some(Key::Mouse(4)),
+        "BTN_EXTRA" => return Some(Key::Mouse(5)),
+        _ => {}
+    }
+    let name = name.strip_prefix("KEY_").unwrap_or(name);
+    // rdev puts the side last: "ControlRight", "ShiftLeft", "MetaLeft".
+    for side in ["Left", "Right"] {
+        if let Some(base) = name.strip_suffix(side) {
+            if let Some(key) = modifier(&base.to_lowercase()) {
+                return Some(key(if side == "Left" { Side::Left } else { Side::Right }));
+            }
+        }
+    }
+    let name = name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Num"))
+        .filter(|rest| rest.chars().count() == 1)
+        .unwrap_or(name);
+    Key::parse(name).ok()
+}
+
+/// The middle and side buttons; rdev does not report the other buttons on
+/// macOS.
+pub fn from_rdev_button(button: &rdev::Button) -> Option<Key> {
+    match button {
+        rdev::Button::Middle => Some(Key::Mouse(3)),
+        // Windows reports XBUTTON1 and XBUTTON2 as 1 and 2.
+        #[cfg(target_os = "windows")]
+        rdev::Button::Unknown(1) => Some(Key::Mouse(4)),
+        #[cfg(target_os = "windows")]
+        rdev::Button::Unknown(2) => Some(Key::Mouse(5)),
+        // X11 numbers the side buttons 8 and 9.
+        #[cfg(target_os = "linux")]
+        rdev::Button::Unknown(8) => Some(Key::Mouse(4)),
+        #[cfg(target_os = "linux")]
+        rdev::Button::Unknown(9) => Some(Key::Mouse(5)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hotkey(text: &str) -> Hotkey {
+        Hotkey::parse(text).unwrap()
+    }
+
+    #[test]
+    fn parses_single_keys() {
+        assert_eq!(Key::parse("Space"), Ok(Key::Space));
+        assert_eq!(Key::parse("F9"), Ok(Key::F(9)));
+        assert_eq!(Key::parse("a"), Ok(Key::Char('a')));
+        assert_eq!(Key::parse("7"), Ok(Key::Char('7')));
+        assert_eq!(Key::parse(";"), Ok(Key::Char(';')));
+        assert_eq!(Key::parse(" PageDown "), Ok(Key::PageDown));
+    }
+
+    #[test]
+    fn parses_modifier_sides() {
+        assert_eq!(Key::parse("Ctrl"), Ok(Key::Ctrl(Side::Either)));
+        assert_eq!(Key::parse("RCtrl"), Ok(Key::Ctrl(Side::Right)));
+        assert_eq!(Key::parse("LeftAlt"), Ok(Key::Alt(Side::Left)));
+        assert_eq!(Key::parse("RightShift"), Ok(Key::Shift(Side::Right)));
+        assert_eq!(Key::parse("LCmd"), Ok(Key::Meta(Side::Left)));
+    }
+
+    #[test]
+    fn names_are_case_insensitive_and_have_aliases() {
+        assert_eq!(Key::parse("ESC"), Ok(Key::Escape));
+        assert_eq!(Key::parse("escape"), Ok(Key::Escape));
+        assert_eq!(Key::parse("Return"), Ok(Key::Enter));
+        assert_eq!(Key::parse("control"), Ok(Key::Ctrl(Side::Either)));
+        assert_eq!(Key::parse("Option"), Ok(Key::Alt(Side::Either)));
+        assert_eq!(Key::parse("super"), Ok(Key::Meta(Side::Either)));
+        assert_eq!(Key::parse("Grave"), Ok(Key::Char('`')));
+        assert_eq!(Key::parse("A"), Ok(Key::Char('a')));
+        assert_eq!(Key::parse("UpArrow"), Ok(Key::Up));
+    }
+
+    #[test]
+    fn parses_mouse_buttons() {
+        assert_eq!(Key::parse("Mouse3"), Ok(Key::Mouse(3)));
+        assert_eq!(Key::parse("mouse4"), Ok(Key::Mouse(4)));
+        assert_eq!(Key::parse("Mouse5"), Ok(Key::Mouse(5)));
+        assert!(Key::parse("Mouse1").is_err());
+        assert!(Key::parse("Mouse2").is_err());
+        assert!(Key::parse("Mouse6").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        assert!(Key::parse("").is_err());
+        assert!(Key::parse("F0").is_err());
+        assert!(Key::parse("F25").is_err());
+        assert!(Key::parse("Hyper").is_err());
+        assert!(Key::parse("ab").is_err());
+        assert!(Key::parse("é").is_err());
+    }
+
+    #[test]
+    fn parses_chords_modifiers_first() {
+        let chord = hotkey("Ctrl+Alt+Space");
+        assert_eq!(chord.keys, vec![Key::Ctrl(Side::Either), Key::Alt(Side::Either), Key::Space]);
+        assert_eq!(hotkey("Space+Ctrl"), hotkey("Ctrl+Space"));
+        assert_eq!(hotkey("RCtrl + RShift").keys, vec![Key::Ctrl(Side::Right), Key::Shift(Side::Right)]);
+    }
+
+    #[test]
+    fn rejects_invalid_hotkeys() {
+        assert!(Hotkey::parse("").is_err());
+        assert!(Hotkey::parse("  ").is_err());
+        assert!(Hotkey::parse("Ctrl+").is_err());
+        assert!(Hotkey::parse("A+B").is_err());
+        assert!(Hotkey::parse("Ctrl+Ctrl").is_err());
+        assert!(Hotkey::parse("Ctrl+RCtrl").is_err());
+        assert!(Hotkey::parse("Ctrl+Nope").is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in ["RCtrl", "Ctrl+Alt+Space", "LShift+F12", "Meta+K", "Mouse4", "Ctrl+PageUp", "Alt+,", "CapsLock"] {
+            let parsed = hotkey(text);
+            assert_eq!(parsed.to_string(), text);
+            assert_eq!(hotkey(&parsed.to_string()), parsed);
+        }
+        assert_eq!(hotkey("ctrl+alt+space").to_string(), "Ctrl+Alt+Space");
+    }
+
+    #[test]
+    fn either_side_matches_both_sides() {
+        assert!(Key::Ctrl(Side::Either).matches(&Key::Ctrl(Side::Left)));
+        assert!(Key::Ctrl(Side::Either).matches(&Key::Ctrl(Side::Right)));
+        assert!(!Key::Ctrl(Side::Right).matches(&Key::Ctrl(Side::Left)));
+        assert!(!Key::Ctrl(Side::Left).matches(&Key::Ctrl(Side::Either)));
+    }
+
+    #[test]
+    fn pressed_and_other_keys() {
+        let chord = hotkey("Ctrl+Space");
+        assert!(chord.is_pressed(&[Key::Ctrl(Side::Left), Key::Space]));
+        assert!(!chord.is_pressed(&[Key::Space]));
+        assert!(!chord.has_other_keys(&[Key::Ctrl(Side::Right), Key::Space]));
+        assert!(chord.has_other_keys(&[Key::Ctrl(Side::Right), Key::Space, Key::Char('c')]));
+    }
+
+    #[test]
+    fn overlaps() {
+        assert!(hotkey("Ctrl").overlaps(&hotkey("Ctrl+Space")));
+        assert!(hotkey("Ctrl+Space").overlaps(&hotkey("Ctrl")));
+        assert!(hotkey("RCtrl").overlaps(&hotkey("Ctrl")));
+        assert!(hotkey("RCtrl").overlaps(&hotkey("RCtrl")));
+        assert!(!hotkey("RCtrl").overlaps(&hotkey("LCtrl")));
+        assert!(!hotkey("Ctrl+Space").overlaps(&hotkey("Alt+Space")));
+        assert!(!hotkey("Mouse4").overlaps(&hotkey("Mouse5")));
+    }
+
+    #[test]
+    fn check_overlaps_names_the_clash() {
+        assert!(check_overlaps(&[hotkey("RCtrl"), hotkey("Mouse4"), hotkey("Alt+Space")]).is_ok());
+        assert!(check_overlaps(&[]).is_ok());
+        let err = check_overlaps(&[hotkey("Mouse4"), hotkey("Ctrl"), hotkey("Ctrl+Alt")]).unwrap_err();
+        assert!(err.contains("'Ctrl' and 'Ctrl+Alt'"), "{}", err);
+    }
+
+    #[test]
+    fn maps_native_names() {
+        assert_eq!(from_native_name("ControlRight"), Some(Key::Ctrl(Side::Right)));
+        assert_eq!(from_native_name("ShiftLeft"), Some(Key::Shift(Side::Left)));
+        assert_eq!(from_native_name("Alt"), Some(Key::Alt(Side::Left)));
+        assert_eq!(from_native_name("AltGr"), Some(Key::Alt(Side::Right)));
+        assert_eq!(from_native_name("KeyA"), Some(Key::Char('a')));
+        assert_eq!(from_native_name("Num1"), Some(Key::Char('1')));
+        assert_eq!(from_native_name("KEY_RIGHTCTRL"), Some(Key::Ctrl(Side::Right)));
+        assert_eq!(from_native_name("KEY_SPACE"), Some(Key::Space));
+        assert_eq!(from_native_name("BTN_SIDE"), Some(Key::Mouse(4)));
+        assert_eq!(from_native_name("BTN_EXTRA"), Some(Key::Mouse(5)));
+        assert_eq!(from_native_name("Function"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn maps_x11_side_buttons() {
+        assert_eq!(from_rdev_button(&rdev::Button::Unknown(8)), Some(Key::Mouse(4)));
+        assert_eq!(from_rdev_button(&rdev::Button::Unknown(9)), Some(Key::Mouse(5)));
+        assert_eq!(from_rdev_button(&rdev::Button::Middle), Some(Key::Mouse(3)));
+        assert_eq!(from_rdev_button(&rdev::Button::Left), None);
+    }
+}
@@ -0,0 +1,441 @@
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+/// How the hotkey controls recording.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingMode {
+    /// Record while the hotkey is held.
+    #[default]
+    Hold,
+    /// A tap starts recording hands-free and the next press stops it;
+    /// holding still works as push-to-talk.
+    Toggle,
+    /// Like `Toggle`, but hands-free recording needs a double tap, so a
+    /// stray single tap does nothing.
+    DoubleTap,
+}
+
+/// What the key monitor should do after an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Start,
+    Stop { hold_time_ms: u64 },
+    /// Discard the recording without transcribing it, silently: the hotkey
+    /// was only tapped or was part of another shortcut.
+    Cancel,
+    /// The user pressed the cancel key: discard the recording and say so.
+    Abort { hold_time_ms: u64 },
+}
+
+/// A release followed by a press within this time is contact bounce.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(25);
+/// The second tap of a double tap must start within this time of the first.
+const DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(400);
+
+/// Debounce policy for the hotkey's up/down signal. Presses apply at once,
+/// so recording starts without delay. A release only counts once the key
+/// has stayed up for `window`; if it goes down again first, the release
+/// and press were bounce and both are dropped. A pending release is never
+/// lost: it commits with its original timestamp at the first input after
+/// [`Debounce::deadline`], and the monitors feed an input at that deadline.
+#[derive(Debug, Clone)]
+pub struct Debounce {
+    window: Duration,
+    down: bool,
+    pending_release: Option<Instant>,
+}
+
+impl Debounce {
+    pub fn new(window: Duration) -> Self {
+        Self { window, down: false, pending_release: None }
+    }
+
+    /// Takes the raw state at `now` and returns the debounced state with the
+    /// time it took effect, which is earlier than `now` for a release that
+    /// just committed.
+    pub fn filter(&mut self, raw_down: bool, now: Instant) -> (bool, Instant) {
+        if raw_down {
+            self.pending_release = None;
+            self.down = true;
+            return (true, now);
+        }
+        if !self.down {
+            return (false, now);
+        }
+        let released_at = *self.pending_release.get_or_insert(now);
+        if now.duration_since(released_at) >= self.window {
+            self.down = false;
+            self.pending_release = None;
+            (false, released_at)
+        } else {
+            (true, now)
+        }
+    }
+
+    /// When a pending release commits, if one is pending.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.pending_release.map(|at| at + self.window)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle { last_tap: Option<Instant> },
+    /// Recording while the hotkey is down. A short tap latches into
+    /// hands-free recording when `latch_on_tap` is set.
+    Held { since: Instant, latch_on_tap: bool },
+    /// Recording hands-free; the next press stops it.
+    Latched { since: Instant },
+    /// Stopped or cancelled while the hotkey is still down; its release
+    /// must not count as a tap.
+    WaitingForRelease,
+}
+
+/// Turns the hotkey's up/down state into start, stop and cancel actions.
+/// It is fed timestamps rather than reading the clock, so it behaves the
+/// same on every platform's input source.
+#[derive(Debug, Clone)]
+pub struct KeyStateMachine {
+    mode: RecordingMode,
+    min_hold: Duration,
+    state: State,
+    hotkey_down: bool,
+    debounce: Debounce,
+}
+
+impl KeyStateMachine {
+    pub fn new(mode: RecordingMode, min_hold: Duration) -> Self {
+        Self {
+            mode,
+            min_hold,
+            state: State::Idle { last_tap: None },
+            hotkey_down: false,
+            debounce: Debounce::new(DEBOUNCE_WINDOW),
+        }
+    }
+
+    /// Applies changed settings; takes effect from the next press.
+    pub fn configure(&mut self, mode: RecordingMode, min_hold: Duration) {
+        self.mode = mode;
+        self.min_hold = min_hold;
+    }
+
+    /// Nothing in progress: not recording and not waiting for a release.
+    pub fn is_idle(&self) -> bool {
+        matches!(self.state, State::Idle { .. })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state, State::Held { .. } | State::Latched { .. })
+    }
+
+    /// When the monitor must call [`update`](Self::update) again, even
+    /// without new input, to commit a debounced release.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.debounce.deadline()
+    }
+
+    /// Feeds the current input: whether the whole hotkey is down, whether
+    /// any other key is held with it and whether the cancel key is down.
+    /// Only changes produce actions, so repeating an input is harmless.
+    pub fn update(&mut self, hotkey_down: bool, other_keys: bool, cancel_key: bool, now: Instant) -> Option<KeyAction> {
+        let (hotkey_down, now) = self.debounce.filter(hotkey_down, now);
+        let pressed = hotkey_down && !self.hotkey_down;
+        let released = !hotkey_down && self.hotkey_down;
+        self.hotkey_down = hotkey_down;
+
+        match self.state {
+            State::Idle { last_tap } if pressed => {
+                let latch_on_tap = match self.mode {
+                    RecordingMode::Hold => false,
+                    RecordingMode::Toggle => true,
+                    RecordingMode::DoubleTap => last_tap.is_some_and(|tap| now.duration_since(tap) <= DOUBLE_TAP_WINDOW),
+                };
+                self.state = State::Held { since: now, latch_on_tap };
+                Some(KeyAction::Start)
+            }
+            State::Held { since, .. } | State::Latched { since } if cancel_key => {
+                self.state = if hotkey_down { State::WaitingForRelease } else { State::Idle { last_tap: None } };
+                Some(KeyAction::Abort { hold_time_ms: now.duration_since(since).as_millis() as u64 })
+            }
+            State::Held { .. } if other_keys => {
+                // Ctrl+C and the like: the hotkey is part of another shortcut.
+                self.state = State::WaitingForRelease;
+                Some(KeyAction::Cancel)
+            }
+            State::Held { since, latch_on_tap } if released => {
+                let held = now.duration_since(since);
+                if held >= self.min_hold {
+                    self.state = State::Idle { last_tap: None };
+                    Some(KeyAction::Stop { hold_time_ms: held.as_millis() as u64 })
+                } else if latch_on_tap {
+                    self.state = State::Latched { since };
+                    None
+                } else {
+                    // A tap; in double-tap mode it may be the first of two.
+                    self.state = State::Idle { last_tap: Some(now) };
+                    Some(KeyAction::Cancel)
+                }
+            }
+            State::Latched { since } if pressed => {
+                self.state = State::WaitingForRelease;
+                Some(KeyAction::Stop { hold_time_ms: now.duration_since(since).as_millis() as u64 })
+            }
+            State::WaitingForRelease if !hotkey_down => {
+                self.state = State::Idle { last_tap: None };
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Forgets a recording that was stopped from elsewhere, e.g. the UI's
+    /// stop button during hands-free recording.
+    pub fn reset(&mut self) {
+        self.state = if self.hotkey_down { State::WaitingForRelease } else { State::Idle { last_tap: None } };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN_HOLD: Duration = Duration::from_millis(250);
+
+    /// Drives a state machine with times given in milliseconds from a fixed start.
+    struct Driver {
+        machine: KeyStateMachine,
+        start: Instant,
+    }
+
+    impl Driver {
+        fn new(mode: RecordingMode) -> Self {
+            Self { machine: KeyStateMachine::new(mode, MIN_HOLD), start: Instant::now() }
+        }
+
+        fn at(&self, ms: u64) -> Instant {
+            self.start + Duration::from_millis(ms)
+        }
+
+        fn input(&mut self, ms: u64, down: bool, other_keys: bool, cancel_key: bool) -> Option<KeyAction> {
+            let now = self.at(ms);
+            self.machine.update(down, other_keys, cancel_key, now)
+        }
+
+        fn press(&mut self, ms: u64) -> Option<KeyAction> {
+            self.input(ms, true, false, false)
+        }
+
+        /// Releases at `ms` and feeds the debounce deadline so it commits.
+        fn release(&mut self, ms: u64) -> Option<KeyAction> {
+            assert_eq!(self.input(ms, false, false, false), None);
+            let deadline = self.machine.deadline().expect("release should be pending");
+            self.machine.update(false, false, false, deadline)
+        }
+
+        fn tap(&mut self, ms: u64, length: u64) -> Option<KeyAction> {
+            assert_eq!(self.press(ms), Some(KeyAction::Start));
+            self.release(ms + length)
+        }
+    }
+
+    #[test]
+    fn hold_records_while_held() {
+        let mut d = Driver::new(RecordingMode::Hold);
+        assert_eq!(d.press(0), Some(KeyAction::Start));
+        assert!(d.machine.is_recording());
+        assert_eq!(d.press(500), None);
+        assert_eq!(d.release(1000), Some(KeyAction::Stop { hold_time_ms: 1000 }));
+        assert!(d.machine.is_idle());
+    }
+
+    #[test]
+    fn hold_cancels_a_tap_shorter_than_min_hold() {
+        let mut d = Driver::new(RecordingMode::Hold);
+        assert_eq!(d.tap(0, 100), Some(KeyAction::Cancel));
+        assert!(d.machine.is_idle());
+        // Exactly the minimum counts as a hold.
+        assert_eq!(d.tap(1000, 250), Some(KeyAction::Stop { hold_time_ms: 250 }));
+    }
+
+    #[test]
+    fn toggle_latches_on_a_tap_and_the_next_press_stops() {
+        let mut d = Driver::new(RecordingMode::Toggle);
+        assert_eq!(d.tap(0, 100), None);
+        assert!(d.machine.is_recording());
+        assert_eq!(d.press(3000), Some(KeyAction::Stop { hold_time_ms: 3000 }));
+        assert!(!d.machine.is_recording());
+        // The stop press's release is not a new tap.
+        assert_eq!(d.release(3100), None);
+        assert!(d.machine.is_idle());
+    }
+
+    #[test]
+    fn toggle_still_works_as_push_to_talk() {
+        let mut d = Driver::new(RecordingMode::Toggle);
+        assert_eq!(d.tap(0, 800), Some(KeyAction::Stop { hold_time_ms: 800 }));
+        assert!(d.machine.is_idle());
+    }
+
+    #[test]
+    fn double_tap_ignores_a_single_tap() {
+        let mut d = Driver::new(RecordingMode::DoubleTap);
+        assert_eq!(d.tap(0, 100), Some(KeyAction::Cancel));
+        assert!(d.machine.is_idle());
+    }
+
+    #[test]
+    fn double_tap_latches_when_the_second_tap_is_inside_the_window() {
+        let mut d = Driver::new(RecordingMode::DoubleTap);
+        assert_eq!(d.tap(0, 100), Some(KeyAction::Cancel));
+        // The first tap counts from its release at 100 ms.
+        assert_eq!(d.tap(100 + 400, 100), None);
+        assert!(d.machine.is_recording());
+        assert_eq!(d.press(2000), Some(KeyAction::Stop { hold_time_ms: 1500 }));
+    }
+
+    #[test]
+    fn double_tap_does_not_latch_when_the_second_tap_is_outside_the_window() {
+        let mut d = Driver::new(RecordingMode::DoubleTap);
+        assert_eq!(d.tap(0, 100), Some(KeyAction::Cancel));
+        assert_eq!(d.tap(100 + 401, 100), Some(KeyAction::Cancel));
+        assert!(d.machine.is_idle());
+    }
+
+    #[test]
+    fn double_tap_window_starts_over_after_a_recording() {
+        let mut d = Driver::new(RecordingMode::DoubleTap);
+        assert_eq!(d.tap(0, 500), Some(KeyAction::Stop { hold_time_ms: 500 }));
+        assert_eq!(d.tap(600, 100), Some(KeyAction::Cancel));
+    }
+
+    #[test]
+    fn cancel_key_while_held_waits_for_the_release() {
+        let mut d = Driver::new(RecordingMode::Toggle);
+        assert_eq!(d.press(0), Some(KeyAction::Start));
+        assert_eq!(d.input(300, true, false, true), Some(KeyAction::Abort { hold_time_ms: 300 }));
+        assert!(!d.machine.is_recording() && !d.machine.is_idle());
+        // A short hold would normally latch in toggle mode; not after a cancel.
+        assert_eq!(d.release(350), None);
+        assert!(d.machine.is_idle());
+    }
+
+    #[test]
+    fn cancel_key_while_latched_goes_straight_to_idle() {
+        let mut d = Driver::new(RecordingMode::Toggle);
+        assert_eq!(d.tap(0, 100), None);
+        assert_eq!(d.input(2000, false, false, true), Some(KeyAction::Abort { hold_time_ms: 2000 }));
+        assert!(d.machine.is_idle());
+        assert_eq!(d.press(3000), Some(KeyAction::Start));
+    }
+
+    #[test]
+    fn other_keys_cancel_and_then_wait_for_release() {
+        let mut d = Driver::new(RecordingMode::Hold);
+        assert_eq!(d.press(0), Some(KeyAction::Start));
+        assert_eq!(d.input(400, true, true, false), Some(KeyAction::Cancel));
+        assert!(!d.machine.is_recording() && !d.machine.is_idle());
+        assert_eq!(d.input(500, true, false, false), None);
+        assert_eq!(d.release(1000), None);
+        assert!(d.machine.is_idle());
+    }
+
+    #[test]
+    fn other_keys_do_not_stop_a_latched_recording() {
+        let mut d = Driver::new(RecordingMode::Toggle);
+        assert_eq!(d.tap(0, 100), None);
+        assert_eq!(d.input(500, false, true, false), None);
+        assert!(d.machine.is_recording());
+    }
+
+    #[test]
+    fn reset_while_the_key_is_down_waits_for_the_release() {
+        let mut d = Driver::new(RecordingMode::Toggle);
+        assert_eq!(d.press(0), Some(KeyAction::Start));
+        d.machine.reset();
+        assert!(!d.machine.is_recording() && !d.machine.is_idle());
+        // The release neither latches nor stops anything.
+        assert_eq!(d.release(100), None);
+        assert!(d.machine.is_idle());
+        assert_eq!(d.press(1000), Some(KeyAction::Start));
+    }
+
+    #[test]
+    fn reset_while_the_key_is_up_goes_to_idle() {
+        let mut d = Driver::new(RecordingMode::Toggle);
+        assert_eq!(d.tap(0, 100), None);
+        d.machine.reset();
+        assert!(d.machine.is_idle());
+        assert_eq!(d.press(1000), Some(KeyAction::Start));
+    }
+
+    #[test]
+    fn configure_applies_from_the_next_press() {
+        let mut d = Driver::new(RecordingMode::Hold);
+        assert_eq!(d.press(0), Some(KeyAction::Start));
+        d.machine.configure(RecordingMode::Toggle, MIN_HOLD);
+        assert_eq!(d.release(100), Some(KeyAction::Cancel));
+        assert_eq!(d.tap(1000, 100), None);
+        assert!(d.machine.is_recording());
+    }
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn debounce_applies_presses_at_once() {
+        let start = Instant::now();
+        let mut debounce = Debounce::new(DEBOUNCE_WINDOW);
+        assert_eq!(debounce.filter(true, start), (true, start));
+        assert_eq!(debounce.deadline(), None);
+    }
+
+    #[test]
+    fn debounce_drops_a_release_and_press_inside_the_window() {
+        let start = Instant::now();
+        let mut debounce = Debounce::new(DEBOUNCE_WINDOW);
+        debounce.filter(true, start);
+        assert_eq!(debounce.filter(false, ms(start, 100)), (true, ms(start, 100)));
+        assert_eq!(debounce.filter(true, ms(start, 110)), (true, ms(start, 110)));
+        // The bounced release is forgotten; a later one starts its own window.
+        assert_eq!(debounce.filter(false, ms(start, 200)), (true, ms(start, 200)));
+        assert_eq!(debounce.filter(false, ms(start, 224)), (true, ms(start, 224)));
+        assert_eq!(debounce.filter(false, ms(start, 225)), (false, ms(start, 200)));
+    }
+
+    #[test]
+    fn debounce_commits_a_release_with_its_original_time() {
+        let start = Instant::now();
+        let mut debounce = Debounce::new(DEBOUNCE_WINDOW);
+        debounce.filter(true, start);
+        debounce.filter(false, ms(start, 100));
+        assert_eq!(debounce.deadline(), Some(ms(start, 125)));
+        // Fed late, e.g. by the next unrelated key event.
+        assert_eq!(debounce.filter(false, ms(start, 900)), (false, ms(start, 100)));
+        assert_eq!(debounce.deadline(), None);
+        assert_eq!(debounce.filter(false, ms(start, 1000)), (false, ms(start, 1000)));
+    }
+
+    #[test]
+    fn debounce_deadline_is_cleared_by_a_new_press() {
+        let start = Instant::now();
+        let mut debounce = Debounce::new(DEBOUNCE_WINDOW);
+        debounce.filter(true, start);
+        debounce.filter(false, ms(start, 100));
+        assert!(debounce.deadline().is_some());
+        debounce.filter(true, ms(start, 110));
+        assert_eq!(debounce.deadline(), None);
+    }
+
+    #[test]
+    fn debounced_bounce_does_not_stop_a_hold() {
+        let mut d = Driver::new(RecordingMode::Hold);
+        assert_eq!(d.press(0), Some(KeyAction::Start));
+        assert_eq!(d.input(500, false, false, false), None);
+        assert_eq!(d.press(510), None);
+        assert!(d.machine.is_recording());
+        assert_eq!(d.release(1000), Some(KeyAction::Stop { hold_time_ms: 1000 }));
+    }
+}
@@ -3,10 +3,13 @@
 
 use tauri::{command, Emitter, Manager, PhysicalPosition};
 mod platform {
+    pub mod input;
     #[cfg(target_os = "windows")]
     pub mod windows;
     #[cfg(target_os = "macos")]
     pub mod macos;
+    #[cfg(target_os = "linux")]
+    pub mod linux;
 }
 mod audio;
 mod tray;
@@ -15,6 +18,25 @@ mod transcription;
 mod textinjection;
 mod history;
 mod update;
+mod streaming;
+mod postprocess;
+mod formatting;
+mod pipeline;
+mod replacements;
+mod disfluency;
+mod text;
+mod normalization;
+mod codemode;
+mod focus;
+mod profiles;
+mod hotkey;
+mod keystate;
+mod actions;
+mod dictation;
+mod pending;
+mod undo;
+mod output;
+use actions::{DictationAction, HotkeyBinding};
 use history::{History, TranscriptionEntry};
 use chrono::Utc;
 use std::sync::OnceLock;
@@ -22,15 +44,179 @@ use uuid::Uuid;
 use std::fs;
 use base64;
 use dirs::config_dir;
-use std::time::Duration;
 
 static HISTORY: OnceLock<History> = OnceLock::new();
 
-pub fn handle_stop_recording_workflow(app: &tauri::AppHandle, restore_focus: Option<Box<dyn FnOnce()>>, hold_time_ms: Option<u64>) -> Result<(), String> {
+fn dictation_task(settings: &settings::Settings) -> transcription::Task {
+    if settings.translate_mode {
+        transcription::Task::Translate
+    } else {
+        transcription::Task::Transcribe
+    }
+}
+
+/// The settings for one dictation: the global settings, then the profile
+/// of the `target` window, then the hotkey's binding. Also returns the name
+/// of the profile.
+fn dictation_settings(target: Option<&focus::ActiveWindow>, binding: &HotkeyBinding) -> Result<(settings::Settings, Option<String>), String> {
+    let settings = settings::get_settings().map_err(|e| e.to_string())?;
+    let (mut settings, profile) = profiles::settings_for(&settings, target);
+    binding.apply(&mut settings);
+    Ok((settings, profile))
+}
+
+pub fn handle_start_recording_workflow(app: &tauri::AppHandle, target: Option<&focus::ActiveWindow>, binding: &HotkeyBinding) -> Result<(), String> {
+    audio::start_recording().map_err(|e| e.to_string())?;
+
+    let (settings, _) = dictation_settings(target, binding)?;
+    if settings.streaming_enabled {
+        let provider = transcription::Provider::from_settings(&settings)?;
+        streaming::start(app.clone(), provider, dictation_task(&settings));
+    }
+    Ok(())
+}
+
+/// Stops the recording and throws it away without transcribing, e.g. when
+/// the hotkey turned out to be part of another shortcut or was only tapped.
+pub fn handle_cancel_recording_workflow(app: &tauri::AppHandle) -> Result<(), String> {
+    let result = discard_recording();
+    dictation::discard(app);
+    result
+}
+
+fn discard_recording() -> Result<(), String> {
+    audio::stop_recording().map_err(|e| e.to_string())?;
+    streaming::cancel();
+    let _ = std::fs::remove_file(std::env::temp_dir().join("vwisper_audio_latest.wav"));
+    Ok(())
+}
+
+/// Discards the recording because the user pressed the cancel key. Nothing
+/// is sent to the API; the pill shows `cancelled` and, if enabled, the
+/// attempt is kept in history.
+pub fn handle_abort_recording_workflow(app: &tauri::AppHandle, target: Option<focus::ActiveWindow>, binding: &HotkeyBinding, hold_time_ms: Option<u64>) -> Result<(), String> {
+    let result = discard_recording();
+    if !dictation::cancel(app) {
+        return result;
+    }
+    result?;
+
+    let (settings, profile) = dictation_settings(target.as_ref(), binding)?;
+    if settings.save_history && settings.save_cancelled_to_history {
+        let history = HISTORY.get_or_init(History::new);
+        history.add_entry(TranscriptionEntry {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            text: String::new(),
+            round_trip_ms: None,
+            hold_time_ms,
+            status: "cancelled".to_string(),
+            wav_path: None,
+            mode: Some(dictation_task(&settings).as_str().to_string()),
+            source_language: None,
+            raw_text: None,
+            window_title: target.as_ref().and_then(|w| w.title.clone()),
+            app_name: target.and_then(|w| w.process_name),
+            profile,
+            action: Some(binding.action.as_str().to_string()),
+            undone_at: None,
+        });
+    }
+    Ok(())
+}
+
+/// A recording started by a press of the hotkey, held by the key monitor
+/// until it is stopped or cancelled.
+pub struct StartedRecording {
+    /// The window focused at the start.
+    pub target: Option<focus::ActiveWindow>,
+}
+
+/// Carries out what the key state machine decided; shared by the platform
+/// key monitors. `dictation` is the binding of the hotkey in use. `recording`
+/// is `None` when the press didn't start one, e.g. because the previous
+/// dictation was still being transcribed; its cancel or stop must then
+/// leave the audio alone.
+pub fn handle_key_action(app: &tauri::AppHandle, action: keystate::KeyAction, dictation: &HotkeyBinding, recording: &mut Option<StartedRecording>) {
+    match action {
+        keystate::KeyAction::Start => {
+            // Capture the currently active window before showing our window
+            let target = focus::capture_active_window();
+            if !dictation::start(app) {
+                // The previous dictation is still being transcribed.
+                return;
+            }
+            let _ = app.emit_to("main", "start-recording", "");
+            match handle_start_recording_workflow(app, target.as_ref(), dictation) {
+                Ok(()) => *recording = Some(StartedRecording { target }),
+                Err(e) => {
+                    eprintln!("Error in handle_start_recording_workflow: {}", e);
+                    dictation::fail(app);
+                }
+            }
+        }
+        keystate::KeyAction::Cancel => {
+            if recording.take().is_none() {
+                return;
+            }
+            if let Err(e) = handle_cancel_recording_workflow(app) {
+                eprintln!("Error in handle_cancel_recording_workflow: {}", e);
+            }
+        }
+        keystate::KeyAction::Abort { hold_time_ms } => {
+            let Some(started) = recording.take() else {
+                return;
+            };
+            let app_handle = app.clone();
+            let binding = dictation.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = handle_abort_recording_workflow(&app_handle, started.target, &binding, Some(hold_time_ms)) {
+                    eprintln!("Error in handle_abort_recording_workflow: {}", e);
+                }
+            });
+        }
+        keystate::KeyAction::Stop { hold_time_ms } => {
+            let Some(started) = recording.take() else {
+                return;
+            };
+            if !dictation::stop(app) {
+                return;
+            }
+            let _ = app.emit_to("main", "stop-recording", "");
+            spawn_stop_recording_workflow(app, started.target, dictation.clone(), Some(hold_time_ms));
+
+            // Emit the hold time for potential frontend use
+            let _ = app.emit_to("main", "hold-time", hold_time_ms);
+        }
+    }
+}
+
+/// Runs the stop workflow off the caller's thread and ends the dictation
+/// cycle with its outcome.
+fn spawn_stop_recording_workflow(app: &tauri::AppHandle, target: Option<focus::ActiveWindow>, binding: HotkeyBinding, hold_time_ms: Option<u64>) {
+    let app_handle = app.clone();
+    std::thread::spawn(move || {
+        match handle_stop_recording_workflow(&app_handle, target, &binding, hold_time_ms) {
+            Ok(()) => {
+                dictation::succeed(&app_handle);
+            }
+            Err(e) => {
+                eprintln!("Error in handle_stop_recording_workflow: {}", e);
+                dictation::fail(&app_handle);
+            }
+        }
+    });
+}
+
+/// `target` is the window that was focused when recording started; its
+/// profile is applied and the text is sent to the outputs, by default typed
+/// back into it. `binding` is the hotkey that started the recording.
+pub fn handle_stop_recording_workflow(app: &tauri::AppHandle, target: Option<focus::ActiveWindow>, binding: &HotkeyBinding, hold_time_ms: Option<u64>) -> Result<(), String> {
     audio::stop_recording().map_err(|e| e.to_string())?;
     
-    let settings = settings::get_settings().map_err(|e| e.to_string())?;
-    let api_key = settings.groq_api_key.ok_or("No Groq API key set")?;
+    let (settings, profile) = dictation_settings(target.as_ref(), binding)?;
+    let provider = transcription::Provider::from_settings(&settings)?;
+    let task = dictation_task(&settings);
     let temp_dir = std::env::temp_dir();
     let file_path = temp_dir.join("vwisper_audio_latest.wav");
     let id = Uuid::new_v4().to_string();
@@ -47,32 +233,52 @@ pub fn handle_stop_recording_workflow(app: &tauri::AppHandle, restore_focus: Opt
         }
     }
     
-    let result = transcription::transcribe_audio(file_path.to_str().unwrap(), &api_key);
+    // Streaming sessions have already transcribed most of the clip; fall back
+    // to uploading the whole recording if there was none or a chunk failed.
+    let result = match streaming::finish() {
+        Some(result) => result,
+        None => transcription::transcribe_audio(file_path.to_str().unwrap(), &provider, task),
+    };
     
-    if result.status == "success" && !result.text.is_empty() {
-        let _ = app.emit_to("main", "transcription-result", &result.text);
-        
-        // Restore focus to the original window before injecting text
-        if let Some(restore_fn) = restore_focus {
-            restore_fn();
-            // Give the window a moment to gain focus
-            std::thread::sleep(Duration::from_millis(100));
-        }
-        
-        match textinjection::inject_text(&result.text) {
-            Ok(_) => {
-                let _ = app.emit_to("main", "injection-status", "success");
-            }
-            Err(e) => {
-                eprintln!("Text injection failed: {}", e);
-                let _ = app.emit_to("main", "injection-status", "error");
-                let _ = app.emit_to("main", "injection-error", &e.to_string());
+    let mut text = result.text.clone();
+    if result.status == "success" && !text.is_empty() {
+        if let Some(llm) = postprocess::LlmConfig::from_settings(&settings) {
+            match postprocess::clean_up(&text, &llm) {
+                Ok(cleaned) => text = cleaned,
+                // The raw instruction is not the answer, so typing it would be wrong.
+                Err(e) if binding.action == DictationAction::Command => {
+                    let error_msg = format!("LLM command failed: {}", e);
+                    let _ = app.emit_to("main", "transcription-error", &error_msg);
+                    return Err(error_msg);
+                }
+                Err(e) => eprintln!("LLM cleanup failed, using raw transcript: {}", e),
             }
         }
+        text = pipeline::process(&text, &settings, result.language.as_deref());
+    }
+    let raw_text = if text != result.text { Some(result.text.clone()) } else { None };
+
+    // Kept in history even if transcription or an output fails; the
+    // dictation still fails.
+    let mut outcome = Ok(());
+    if result.status == "success" && !text.is_empty() {
+        let _ = app.emit_to("main", "transcription-result", &text);
+        
+        dictation::injecting(app);
+        outcome = output::deliver(app, &output::Transcript {
+            text: &text,
+            target: target.as_ref(),
+            settings: &settings,
+            profile: profile.as_deref(),
+            action: binding.action,
+            language: result.language.as_deref(),
+            history_id: settings.save_history.then_some(id.as_str()),
+        });
     } else {
         let error_msg = result.error.unwrap_or_else(|| "Transcription failed".to_string());
         eprintln!("Transcription failed: {}", error_msg);
         let _ = app.emit_to("main", "transcription-error", &error_msg);
+        outcome = Err(error_msg);
     }
     
     if settings.save_history {
@@ -80,16 +286,24 @@ pub fn handle_stop_recording_workflow(app: &tauri::AppHandle, restore_focus: Opt
         history.add_entry(TranscriptionEntry {
             id,
             timestamp: Utc::now(),
-            text: result.text.clone(),
+            text,
             round_trip_ms: Some(result.round_trip_ms),
             hold_time_ms: hold_time_ms,
             status: result.status.clone(),
             wav_path,
+            mode: Some(task.as_str().to_string()),
+            source_language: result.language.clone(),
+            raw_text,
+            window_title: target.as_ref().and_then(|w| w.title.clone()),
+            app_name: target.and_then(|w| w.process_name),
+            profile,
+            action: Some(binding.action.as_str().to_string()),
+            undone_at: None,
         });
     } else {
         let _ = std::fs::remove_file(&file_path);
     }
-    Ok(())
+    outcome
 }
 
 fn main() {
@@ -138,6 +352,11 @@ fn main() {
                 let app_handle = app.handle().clone();
                 platform::macos::start_global_key_monitor(app_handle);
             }
+            #[cfg(target_os = "linux")]
+            {
+                let app_handle = app.handle().clone();
+                platform::linux::start_global_key_monitor(app_handle);
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -146,6 +365,43 @@ fn main() {
             manual_stop_recording,
             settings::get_settings,
             settings::save_settings,
+            settings::set_translate_mode,
+            settings::set_code_mode,
+            settings::set_hotkey,
+            settings::set_hotkey_bindings,
+            settings::set_recording_mode,
+            settings::set_cancel_key,
+            settings::save_llm_settings,
+            settings::save_injection_timing,
+            settings::set_focus_change_policy,
+            settings::set_save_cancelled_to_history,
+            settings::set_min_hold_ms,
+            settings::set_strip_trailing_period,
+            settings::set_injection_method,
+            settings::save_number_settings,
+            settings::set_disfluency_filter_enabled,
+            settings::set_formatting_commands_enabled,
+            settings::set_language,
+            settings::save_transcription_settings,
+            formatting::get_formatting_commands,
+            formatting::save_formatting_commands,
+            formatting::reset_formatting_commands,
+            replacements::get_replacement_rules,
+            replacements::save_replacement_rule,
+            replacements::delete_replacement_rule,
+            replacements::import_replacement_rules,
+            replacements::export_replacement_rules,
+            profiles::get_profiles,
+            profiles::save_profile,
+            profiles::delete_profile,
+            focus::get_focus_support,
+            pending::get_pending_text,
+            pending::clear_pending_text,
+            pending::paste_pending_text,
+            settings::set_pending_paste_hotkey,
+            undo::undo_last_dictation,
+            settings::set_undo_hotkey,
+            settings::save_output_settings,
             settings::reset_settings,
             settings::get_settings_path,
             get_transcription_history,
@@ -161,44 +417,36 @@ fn main() {
 }
 
 #[command]
-fn start_audio_recording() -> Result<(), String> {
-    audio::start_recording().map_err(|e| e.to_string())
+fn start_audio_recording(app: tauri::AppHandle) -> Result<(), String> {
+    if !dictation::start(&app) {
+        return Err("A dictation is already in progress".to_string());
+    }
+    handle_start_recording_workflow(&app, None, &HotkeyBinding::default()).inspect_err(|_| {
+        dictation::fail(&app);
+    })
 }
 
 #[command]
 fn stop_audio_recording(app: tauri::AppHandle) -> Result<(), String> {
-    handle_stop_recording_workflow(&app, None, None)
+    if !dictation::stop(&app) {
+        return Err("Not recording".to_string());
+    }
+    let result = handle_stop_recording_workflow(&app, None, &HotkeyBinding::default(), None);
+    if result.is_ok() {
+        dictation::succeed(&app);
+    } else {
+        dictation::fail(&app);
+    }
+    result
 }
 
 #[command]
 fn manual_stop_recording(app: tauri::AppHandle) -> Result<(), String> {
     // Check if recording is actually in progress
-    if !audio::is_recording() {
+    if !audio::is_recording() || !dictation::stop(&app) {
         return Ok(());
     }
-    
-    // Stop the audio recording first
-    audio::stop_recording().map_err(|e| e.to_string())?;
-    
-    // Emit loading state
-    let _ = app.emit_to("main", "pill-state", "loading");
-    
-    // Handle the stop recording workflow in a separate thread
-    let app_handle_clone = app.clone();
-    std::thread::spawn(move || {
-        let result = handle_stop_recording_workflow(&app_handle_clone, None, None);
-        if let Err(e) = result {
-            eprintln!("Error in handle_stop_recording_workflow: {}", e);
-            let _ = app_handle_clone.emit_to("main", "pill-state", "error");
-            // Keep error state visible for 3 seconds before hiding
-            std::thread::sleep(Duration::from_secs(3));
-        }
-        let _ = app_handle_clone.emit_to("main", "pill-state", "idle");
-        if let Some(window) = app_handle_clone.get_webview_window("main") {
-            let _ = window.hide();
-        }
-    });
-    
+    spawn_stop_recording_workflow(&app, None, HotkeyBinding::default(), None);
     Ok(())
 }
 
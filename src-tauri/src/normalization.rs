@@ -0,0 +1,714 @@
+use serde::{Deserialize, Serialize};
+use crate::text::Token;
+
+/// How plain numbers are written. Numbers with a currency, unit, percentage,
+/// decimal point, time or date are always written as digits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberStyle {
+    /// Every number as digits: "three apples" -> "3 apples".
+    Digits,
+    /// Style-guide convention: zero to nine stay words, 10 and up are digits.
+    #[default]
+    Mixed,
+}
+
+/// The spoken-number vocabulary of one language. Adding a language means
+/// adding a table and an entry in `table_for`.
+pub struct LanguageTable {
+    pub units: &'static [(&'static str, u64)],
+    pub tens: &'static [(&'static str, u64)],
+    pub hundred: &'static str,
+    pub scales: &'static [(&'static str, u64)],
+    /// Ordinal words with the cardinal they stand for.
+    pub ordinals: &'static [(&'static str, u64)],
+    pub ordinal_suffix: fn(u64) -> &'static str,
+    pub conjunction: &'static str,
+    pub point: &'static str,
+    /// Spoken "0" inside decimals, times and years ("three oh five").
+    pub oh: &'static str,
+    pub negative: &'static [&'static str],
+    pub percent: &'static [&'static str],
+    /// Currency word -> symbol, written before the amount.
+    pub currencies: &'static [(&'static str, &'static str)],
+    pub minor_currency: &'static [&'static str],
+    /// Unit word -> abbreviation.
+    pub measures: &'static [(&'static str, &'static str)],
+    pub months: &'static [&'static str],
+    /// Words after which a number reads as a year: "in nineteen ninety".
+    pub year_words: &'static [&'static str],
+    pub oclock: &'static str,
+    pub am: &'static str,
+    pub pm: &'static str,
+}
+
+fn english_ordinal_suffix(n: u64) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+static ENGLISH: LanguageTable = LanguageTable {
+    units: &[
+        ("zero", 0), ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5),
+        ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9), ("ten", 10), ("eleven", 11),
+        ("twelve", 12), ("thirteen", 13), ("fourteen", 14), ("fifteen", 15), ("sixteen", 16),
+        ("seventeen", 17), ("eighteen", 18), ("nineteen", 19),
+    ],
+    tens: &[
+        ("twenty", 20), ("thirty", 30), ("forty", 40), ("fifty", 50),
+        ("sixty", 60), ("seventy", 70), ("eighty", 80), ("ninety", 90),
+    ],
+    hundred: "hundred",
+    scales: &[("thousand", 1_000), ("million", 1_000_000), ("billion", 1_000_000_000)],
+    ordinals: &[
+        ("first", 1), ("second", 2), ("third", 3), ("fourth", 4), ("fifth", 5), ("sixth", 6),
+        ("seventh", 7), ("eighth", 8), ("ninth", 9), ("tenth", 10), ("eleventh", 11),
+        ("twelfth", 12), ("thirteenth", 13), ("fourteenth", 14), ("fifteenth", 15),
+        ("sixteenth", 16), ("seventeenth", 17), ("eighteenth", 18), ("nineteenth", 19),
+        ("twentieth", 20), ("thirtieth", 30), ("fortieth", 40), ("fiftieth", 50),
+        ("sixtieth", 60), ("seventieth", 70), ("eightieth", 80), ("ninetieth", 90),
+        ("hundredth", 100), ("thousandth", 1_000), ("millionth", 1_000_000),
+    ],
+    ordinal_suffix: english_ordinal_suffix,
+    conjunction: "and",
+    point: "point",
+    oh: "oh",
+    negative: &["minus", "negative"],
+    percent: &["percent", "per cent"],
+    currencies: &[
+        ("dollars", "$"), ("dollar", "$"), ("bucks", "$"),
+        ("euros", "€"), ("euro", "€"),
+    ],
+    minor_currency: &["cents", "cent"],
+    measures: &[
+        ("kilometers", "km"), ("kilometres", "km"), ("kilometer", "km"), ("kilometre", "km"),
+        ("meters", "m"), ("metres", "m"), ("meter", "m"), ("metre", "m"),
+        ("centimeters", "cm"), ("centimetres", "cm"), ("millimeters", "mm"), ("millimetres", "mm"),
+        ("kilograms", "kg"), ("kilogram", "kg"), ("grams", "g"), ("gram", "g"),
+        ("milliseconds", "ms"), ("millisecond", "ms"),
+        ("kilobytes", "KB"), ("megabytes", "MB"), ("gigabytes", "GB"), ("terabytes", "TB"),
+        ("degrees", "°"), ("degree", "°"),
+    ],
+    months: &[
+        "january", "february", "march", "april", "may", "june", "july",
+        "august", "september", "october", "november", "december",
+    ],
+    year_words: &["in", "since"],
+    oclock: "o'clock",
+    am: "am",
+    pm: "pm",
+};
+
+pub fn table_for(language: &str) -> Option<&'static LanguageTable> {
+    match language {
+        "en" => Some(&ENGLISH),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct NumberWord {
+    value: u64,
+    kind: Kind,
+    ordinal: bool,
+}
+
+impl LanguageTable {
+    fn lookup(list: &[(&'static str, u64)], word: &str) -> Option<u64> {
+        list.iter().find(|(w, _)| *w == word).map(|(_, v)| *v)
+    }
+
+    fn classify(&self, word: &str) -> Option<NumberWord> {
+        let kind_of = |value: u64| match value {
+            0..=9 => Kind::Unit,
+            10..=19 => Kind::Teen,
+            20..=99 => Kind::Tens,
+            100 => Kind::Hundred,
+            _ => Kind::Scale,
+        };
+        if let Some(value) = Self::lookup(self.units, word).or_else(|| Self::lookup(self.tens, word)) {
+            return Some(NumberWord { value, kind: kind_of(value), ordinal: false });
+        }
+        if word == self.hundred {
+            return Some(NumberWord { value: 100, kind: Kind::Hundred, ordinal: false });
+        }
+        if let Some(value) = Self::lookup(self.scales, word) {
+            return Some(NumberWord { value, kind: Kind::Scale, ordinal: false });
+        }
+        Self::lookup(self.ordinals, word).map(|value| NumberWord { value, kind: kind_of(value), ordinal: true })
+    }
+
+    fn digit(&self, word: &str) -> Option<u64> {
+        if word == self.oh {
+            return Some(0);
+        }
+        Self::lookup(self.units, word).filter(|v| *v < 10)
+    }
+
+    /// Whether `word` belongs to a spoken number, e.g. "twenty" or "oh".
+    pub fn is_number_word(&self, word: &str) -> bool {
+        word.split('-').all(|part| self.classify(part).is_some() || part == self.oh)
+    }
+
+    fn month(&self, word: &str) -> Option<&'static str> {
+        self.months.iter().copied().find(|m| *m == word)
+    }
+}
+
+/// A run of number words recognised at some position.
+#[derive(Debug, Clone)]
+struct Spoken {
+    value: u64,
+    decimals: Option<String>,
+    ordinal: bool,
+    negative: bool,
+    /// Number of whitespace tokens consumed.
+    tokens: usize,
+    /// Number of number words, so "third" (1) can be told from "twenty third" (2).
+    words: usize,
+}
+
+/// Lowercase words of `tokens`, with hyphenated words ("twenty-five") split,
+/// tagged with the index of the token they came from. Stops after a token
+/// that carries punctuation so numbers don't run across commas.
+fn words_from(tokens: &[Token]) -> Vec<(usize, String)> {
+    let mut words = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 && !token.lead.is_empty() {
+            break;
+        }
+        for part in token.key().split('-') {
+            words.push((i, part.to_string()));
+        }
+        if !token.trail.is_empty() {
+            break;
+        }
+    }
+    words
+}
+
+/// Parses a cardinal or ordinal from the start of `words`, returning its value,
+/// whether it ended in an ordinal and how many words were consumed.
+fn parse_cardinal(table: &LanguageTable, words: &[(usize, String)]) -> Option<(u64, bool, usize)> {
+    let mut total = 0;
+    let mut current = 0;
+    let mut last: Option<Kind> = None;
+    let mut consumed = 0;
+    let mut ordinal = false;
+
+    for (i, (_, word)) in words.iter().enumerate() {
+        if word == table.conjunction {
+            let next_is_small = words
+                .get(i + 1)
+                .and_then(|(_, w)| table.classify(w))
+                .is_some_and(|w| matches!(w.kind, Kind::Unit | Kind::Teen | Kind::Tens));
+            if matches!(last, Some(Kind::Hundred | Kind::Scale)) && next_is_small {
+                continue;
+            }
+            break;
+        }
+        let Some(number) = table.classify(word) else { break };
+        let allowed = match number.kind {
+            Kind::Unit => matches!(last, None | Some(Kind::Tens | Kind::Hundred | Kind::Scale)) && current % 10 == 0,
+            Kind::Teen => matches!(last, None | Some(Kind::Hundred | Kind::Scale)),
+            Kind::Tens => matches!(last, None | Some(Kind::Hundred | Kind::Scale)),
+            Kind::Hundred => matches!(last, Some(Kind::Unit | Kind::Teen | Kind::Tens)) && current < 100,
+            Kind::Scale => current > 0 && (total == 0 || number.value < largest_scale(total)),
+        };
+        if !allowed {
+            break;
+        }
+        match number.kind {
+            Kind::Hundred => current *= 100,
+            Kind::Scale => {
+                total += current * number.value;
+                current = 0;
+            }
+            _ => current += number.value,
+        }
+        last = Some(number.kind);
+        consumed = i + 1;
+        if number.ordinal {
+            ordinal = true;
+            break;
+        }
+    }
+
+    if consumed == 0 {
+        None
+    } else {
+        Some((total + current, ordinal, consumed))
+    }
+}
+
+fn largest_scale(total: u64) -> u64 {
+    let mut scale = 1;
+    while total / scale >= 1000 {
+        scale *= 1000;
+    }
+    scale
+}
+
+/// Years are read in pairs: "nineteen ninety nine", "twenty twenty four",
+/// "nineteen oh five", "fifteen hundred".
+fn parse_year(table: &LanguageTable, words: &[(usize, String)]) -> Option<(u64, usize)> {
+    let (century, _, first) = parse_cardinal(table, &words[..words.len().min(2)])?;
+    if !(11..=20).contains(&century) {
+        return None;
+    }
+    let rest = &words[first..];
+    if rest.first().is_some_and(|(_, w)| w == table.hundred) {
+        return Some((century * 100, first + 1));
+    }
+    if let (Some((_, oh)), Some((_, digit))) = (rest.first(), rest.get(1)) {
+        if oh == table.oh {
+            let digit = table.digit(digit)?;
+            return Some((century * 100 + digit, first + 2));
+        }
+    }
+    let (year, ordinal, second) = parse_cardinal(table, &rest[..rest.len().min(2)])?;
+    if ordinal || !(10..=99).contains(&year) {
+        return None;
+    }
+    Some((century * 100 + year, first + second))
+}
+
+/// Number of whole tokens covered by the first `words` entries, or `None`
+/// when that would split a hyphenated token.
+fn tokens_covered(words: &[(usize, String)], consumed: usize) -> Option<usize> {
+    let last_token = words[consumed - 1].0;
+    match words.get(consumed) {
+        Some((next_token, _)) if *next_token == last_token => None,
+        _ => Some(last_token + 1),
+    }
+}
+
+fn parse_number(table: &LanguageTable, tokens: &[Token], allow_year: bool) -> Option<Spoken> {
+    let mut offset = 0;
+    let negative = tokens.first().is_some_and(|t| t.trail.is_empty() && table.negative.contains(&t.key().as_str()));
+    if negative {
+        offset = 1;
+    }
+    let words = words_from(&tokens[offset..]);
+
+    if allow_year && !negative {
+        if let Some((year, consumed)) = parse_year(table, &words) {
+            if let Some(covered) = tokens_covered(&words, consumed) {
+                return Some(Spoken { value: year, decimals: None, ordinal: false, negative, tokens: covered, words: consumed });
+            }
+        }
+    }
+
+    let (value, ordinal, consumed) = parse_cardinal(table, &words)?;
+    let mut covered = tokens_covered(&words, consumed)?;
+    let mut spoken = Spoken { value, decimals: None, ordinal, negative, tokens: offset + covered, words: consumed };
+
+    // "three point one four"
+    if !ordinal && words.get(consumed).is_some_and(|(_, w)| w == table.point) {
+        let digits: String = words[consumed + 1..]
+            .iter()
+            .map_while(|(_, w)| table.digit(w))
+            .map(|d| d.to_string())
+            .collect();
+        if !digits.is_empty() {
+            if let Some(with_decimals) = tokens_covered(&words, consumed + 1 + digits.len()) {
+                covered = with_decimals;
+                spoken.decimals = Some(digits);
+                spoken.tokens = offset + covered;
+            }
+        }
+    }
+    Some(spoken)
+}
+
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    if value < 10_000 {
+        return digits;
+    }
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn render_number(spoken: &Spoken) -> String {
+    let mut out = String::new();
+    if spoken.negative {
+        out.push('-');
+    }
+    out.push_str(&group_thousands(spoken.value));
+    if let Some(decimals) = &spoken.decimals {
+        out.push('.');
+        out.push_str(decimals);
+    }
+    out
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn is_bare_word(token: Option<&Token>, word: &str) -> bool {
+    token.is_some_and(|t| t.key() == word)
+}
+
+/// Matches a possibly multi-word phrase ("per cent") at the start of `tokens`.
+fn phrase_at(tokens: &[Token], phrase: &str) -> Option<usize> {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let candidate = tokens.get(..words.len())?;
+    let inner_bare = candidate[..candidate.len() - 1].iter().all(|t| t.trail.is_empty());
+    if inner_bare && candidate.iter().zip(&words).all(|(t, w)| t.key() == *w) {
+        Some(words.len())
+    } else {
+        None
+    }
+}
+
+fn meridiem(table: &LanguageTable, token: Option<&Token>) -> Option<&'static str> {
+    let key = token?.key().replace('.', "");
+    if key == table.am {
+        Some("AM")
+    } else if key == table.pm {
+        Some("PM")
+    } else {
+        None
+    }
+}
+
+/// A converted span: the replacement text, how many tokens it covers and,
+/// if it differs from the last covered token's, the punctuation after it.
+struct Rewrite {
+    text: String,
+    tokens: usize,
+    trail: Option<String>,
+}
+
+impl Rewrite {
+    fn new(text: String, tokens: usize) -> Self {
+        Self { text, tokens, trail: None }
+    }
+}
+
+fn rewrite_date(table: &LanguageTable, tokens: &[Token]) -> Option<Rewrite> {
+    // Months are capitalized by Whisper; this also keeps "may" the verb out.
+    let month = table.month(&tokens[0].key())?;
+    if !tokens[0].trail.is_empty() || !tokens[0].starts_uppercase() {
+        return None;
+    }
+    let day = parse_number(table, &tokens[1..], false)?;
+    if day.decimals.is_some() || day.negative || !(1..=31).contains(&day.value) {
+        return None;
+    }
+    let mut text = format!("{} {}", capitalize(month), day.value);
+    let mut used = 1 + day.tokens;
+
+    let day_trail = &tokens[used - 1].trail;
+    if day_trail.is_empty() || day_trail == "," {
+        if let Some(year) = parse_number(table, &tokens[used..], true) {
+            if year.decimals.is_none() && !year.ordinal && (1000..=2999).contains(&year.value) {
+                text.push_str(&format!(", {}", year.value));
+                used += year.tokens;
+            }
+        }
+    }
+    Some(Rewrite::new(text, used))
+}
+
+/// "a.m." swallows the sentence's full stop, so only keep the dot when the
+/// text really ends or a new sentence starts after it.
+fn meridiem_trail(token: &Token, next: Option<&Token>) -> Option<String> {
+    let ends_sentence = next.is_none_or(|n| n.starts_uppercase());
+    match token.trail.strip_prefix('.') {
+        Some(rest) if !ends_sentence => Some(rest.to_string()),
+        _ => None,
+    }
+}
+
+fn rewrite_time(table: &LanguageTable, hour: &Spoken, rest: &[Token]) -> Option<Rewrite> {
+    if hour.ordinal || hour.decimals.is_some() || hour.negative || !(1..=12).contains(&hour.value) {
+        return None;
+    }
+    if is_bare_word(rest.first(), table.oclock) {
+        return Some(Rewrite::new(format!("{}:00", hour.value), 1));
+    }
+    if let Some(suffix) = meridiem(table, rest.first()) {
+        return Some(Rewrite {
+            text: format!("{} {}", hour.value, suffix),
+            tokens: 1,
+            trail: meridiem_trail(&rest[0], rest.get(1)),
+        });
+    }
+
+    // "three thirty pm", "seven oh five am"
+    let words = words_from(rest);
+    let (minutes, consumed) = match words.first() {
+        Some((_, w)) if w == table.oh => (table.digit(&words.get(1)?.1)?, 2),
+        _ => {
+            let (minutes, ordinal, consumed) = parse_cardinal(table, &words)?;
+            if ordinal {
+                return None;
+            }
+            (minutes, consumed)
+        }
+    };
+    if !(0..=59).contains(&minutes) {
+        return None;
+    }
+    let covered = tokens_covered(&words, consumed)?;
+    if rest[covered - 1].trail.is_empty() {
+        if let Some(suffix) = meridiem(table, rest.get(covered)) {
+            return Some(Rewrite {
+                text: format!("{}:{:02} {}", hour.value, minutes, suffix),
+                tokens: covered + 1,
+                trail: meridiem_trail(&rest[covered], rest.get(covered + 1)),
+            });
+        }
+    }
+    None
+}
+
+/// Converts the number at the start of `tokens` together with whatever
+/// follows it (currency, unit, percent, time of day). Pairs such as
+/// "twenty twenty" are only read as a year after a month or a word like
+/// "in", so "eleven twenty dollar bills" keeps its two numbers.
+fn rewrite_number(table: &LanguageTable, previous: Option<&Token>, tokens: &[Token], style: NumberStyle) -> Option<Rewrite> {
+    // Times first, before "twelve fifteen" can be read as the year 1215.
+    if let Some(hour) = parse_number(table, tokens, false) {
+        if tokens[hour.tokens - 1].trail.is_empty() {
+            if let Some(time) = rewrite_time(table, &hour, &tokens[hour.tokens..]) {
+                return Some(Rewrite { tokens: hour.tokens + time.tokens, ..time });
+            }
+        }
+    }
+
+    let year_context = previous.is_some_and(|p| {
+        p.trail.is_empty() && (table.month(&p.key()).is_some() || table.year_words.contains(&p.key().as_str()))
+    });
+    let spoken = parse_number(table, tokens, year_context)?;
+    let number = render_number(&spoken);
+    let rest = &tokens[spoken.tokens..];
+    let joined = tokens[spoken.tokens - 1].trail.is_empty();
+
+    if joined && !spoken.ordinal {
+        for phrase in table.percent {
+            if let Some(len) = phrase_at(rest, phrase) {
+                return Some(Rewrite::new(format!("{}%", number), spoken.tokens + len));
+            }
+        }
+        if let Some((_, symbol)) = table.currencies.iter().find(|(w, _)| is_bare_word(rest.first(), w)) {
+            let mut text = format!("{}{}", symbol, number);
+            let mut used = spoken.tokens + 1;
+            // "five dollars and fifty cents"
+            if spoken.decimals.is_none() && rest[0].trail.is_empty() && is_bare_word(rest.get(1), table.conjunction) {
+                if let Some(cents) = parse_number(table, &rest[2..], false) {
+                    let unit = rest.get(2 + cents.tokens);
+                    let is_minor = table.minor_currency.iter().any(|w| is_bare_word(unit, w));
+                    if is_minor && cents.value < 100 && cents.decimals.is_none() && !cents.ordinal {
+                        text = format!("{}{}.{:02}", symbol, number, cents.value);
+                        used += 1 + cents.tokens + 1;
+                    }
+                }
+            }
+            return Some(Rewrite::new(text, used));
+        }
+        if let Some((_, abbreviation)) = table.measures.iter().find(|(w, _)| is_bare_word(rest.first(), w)) {
+            let separator = if *abbreviation == "°" { "" } else { " " };
+            return Some(Rewrite::new(format!("{}{}{}", number, separator, abbreviation), spoken.tokens + 1));
+        }
+    }
+
+    if spoken.ordinal {
+        // A lone "first" or "second" is usually not a number ("first of all",
+        // "wait a second") unless a month follows: "third of June".
+        let of_month = joined
+            && is_bare_word(rest.first(), "of")
+            && rest.get(1).is_some_and(|t| table.month(&t.key()).is_some());
+        if spoken.words < 2 && !of_month {
+            return None;
+        }
+        return Some(Rewrite::new(
+            format!("{}{}", spoken.value, (table.ordinal_suffix)(spoken.value)),
+            spoken.tokens,
+        ));
+    }
+
+    let small = spoken.value < 10 && spoken.decimals.is_none() && !spoken.negative;
+    if style == NumberStyle::Mixed && small {
+        return None;
+    }
+    Some(Rewrite::new(number, spoken.tokens))
+}
+
+/// Rewrites spoken numbers, ordinals, percentages, currency, units, times
+/// and dates in `text` to their written form.
+pub fn normalize(text: &str, table: &LanguageTable, style: NumberStyle) -> String {
+    text.lines()
+        .map(|line| {
+            let tokens: Vec<Token> = line.split_whitespace().map(Token::parse).collect();
+            let mut out: Vec<String> = Vec::new();
+            let mut i = 0;
+            while i < tokens.len() {
+                let previous = tokens[..i].last();
+                let rewrite = rewrite_date(table, &tokens[i..]).or_else(|| rewrite_number(table, previous, &tokens[i..], style));
+                match rewrite {
+                    Some(rewrite) => {
+                        let trail = rewrite.trail.as_deref().unwrap_or(&tokens[i + rewrite.tokens - 1].trail);
+                        out.push(format!("{}{}{}", tokens[i].lead, rewrite.text, trail));
+                        i += rewrite.tokens;
+                    }
+                    None => {
+                        out.push(tokens[i].render());
+                        i += 1;
+                    }
+                }
+            }
+            out.join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(style: NumberStyle, cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(normalize(input, &ENGLISH, style), *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn cardinals() {
+        check(NumberStyle::Digits, &[
+            ("three apples", "3 apples"),
+            ("zero", "0"),
+            ("twenty-five people", "25 people"),
+            ("one hundred and one", "101"),
+            ("two thousand three hundred", "2300"),
+            ("twelve thousand five hundred", "12,500"),
+            ("three million", "3,000,000"),
+            ("minus four", "-4"),
+            ("three point one four", "3.14"),
+            ("one, two, three", "1, 2, 3"),
+        ]);
+    }
+
+    #[test]
+    fn mixed_style_keeps_small_numbers_as_words() {
+        check(NumberStyle::Mixed, &[
+            ("three apples", "three apples"),
+            ("nine lives", "nine lives"),
+            ("ten apples", "10 apples"),
+            ("forty two", "42"),
+            ("minus four", "-4"),
+            ("two point five", "2.5"),
+            ("five percent", "5%"),
+        ]);
+    }
+
+    #[test]
+    fn ordinals() {
+        check(NumberStyle::Mixed, &[
+            ("the twenty first century", "the 21st century"),
+            ("the one hundredth time", "the 100th time"),
+            ("twenty second", "22nd"),
+            ("the third of June", "the 3rd of June"),
+            ("first of all", "first of all"),
+            ("wait a second", "wait a second"),
+        ]);
+    }
+
+    #[test]
+    fn percentages() {
+        check(NumberStyle::Mixed, &[
+            ("fifty percent", "50%"),
+            ("twelve per cent.", "12%."),
+            ("zero point five percent", "0.5%"),
+        ]);
+    }
+
+    #[test]
+    fn currency() {
+        check(NumberStyle::Mixed, &[
+            ("five dollars", "$5"),
+            ("twenty euros", "€20"),
+            ("five dollars and fifty cents", "$5.50"),
+            ("one hundred bucks", "$100"),
+            ("eleven twenty dollar bills", "11 $20 bills"),
+        ]);
+    }
+
+    #[test]
+    fn units() {
+        check(NumberStyle::Mixed, &[
+            ("five kilometers", "5 km"),
+            ("twenty degrees", "20°"),
+            ("five hundred megabytes", "500 MB"),
+        ]);
+    }
+
+    #[test]
+    fn times() {
+        check(NumberStyle::Mixed, &[
+            ("at three o'clock", "at 3:00"),
+            ("at seven am", "at 7 AM"),
+            ("three thirty pm", "3:30 PM"),
+            ("seven oh five a.m. tomorrow", "7:05 AM tomorrow"),
+            ("Leave at seven p.m.", "Leave at 7 PM."),
+            ("twelve fifteen pm", "12:15 PM"),
+        ]);
+    }
+
+    #[test]
+    fn dates_and_years() {
+        check(NumberStyle::Mixed, &[
+            ("June third", "June 3"),
+            ("March fifteenth, twenty twenty four", "March 15, 2024"),
+            ("born in nineteen ninety nine", "born in 1999"),
+            ("since nineteen oh five", "since 1905"),
+            ("in fifteen hundred", "in 1500"),
+            ("you may go", "you may go"),
+        ]);
+    }
+
+    #[test]
+    fn number_pairs_are_not_years_outside_date_contexts() {
+        check(NumberStyle::Digits, &[
+            ("twenty twenty", "20 20"),
+            ("nineteen ninety nine", "19 99"),
+            ("score nineteen ninety", "score 19 90"),
+        ]);
+    }
+
+    #[test]
+    fn non_numbers_are_left_alone() {
+        check(NumberStyle::Digits, &[
+            ("someone said hello", "someone said hello"),
+            ("oh no", "oh no"),
+        ]);
+    }
+}
@@ -0,0 +1,359 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use chrono::{Local, Utc};
+use dirs::config_dir;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+use crate::actions::DictationAction;
+use crate::focus::{self, ActiveWindow, FocusBackend, FocusChangePolicy};
+use crate::settings::Settings;
+use crate::textinjection::{self, InjectionConfig};
+use crate::{pending, undo};
+
+/// Where a finished dictation goes. Several can be combined, e.g. typing
+/// the text and also keeping it in the notes file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputSink {
+    /// Typed or pasted into the window that was focused when recording started.
+    #[default]
+    Inject,
+    /// Left on the clipboard for the user to paste.
+    Clipboard,
+    /// Appended to a Markdown file under a timestamp heading.
+    Markdown,
+    /// Printed on standard output, for scripts that launch the app.
+    Stdout,
+    /// POSTed as JSON to a URL.
+    Webhook,
+}
+
+/// A dictation ready to be sent to its outputs.
+pub struct Transcript<'a> {
+    pub text: &'a str,
+    /// The window that was focused when recording started.
+    pub target: Option<&'a ActiveWindow>,
+    /// The settings for this dictation, with profile and hotkey applied.
+    pub settings: &'a Settings,
+    pub profile: Option<&'a str>,
+    pub action: DictationAction,
+    pub language: Option<&'a str>,
+    /// The history entry for this dictation, if history is kept.
+    pub history_id: Option<&'a str>,
+}
+
+pub trait Sink {
+    fn name(&self) -> &'static str;
+    fn write(&self, app: &AppHandle, transcript: &Transcript) -> Result<(), String>;
+}
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl OutputSink {
+    pub fn sink(&self, settings: &Settings) -> Box<dyn Sink> {
+        match self {
+            OutputSink::Inject => Box::new(InjectSink),
+            OutputSink::Clipboard => Box::new(ClipboardSink),
+            OutputSink::Markdown => Box::new(MarkdownSink {
+                path: settings
+                    .markdown_notes_path
+                    .clone()
+                    .filter(|path| !path.trim().is_empty())
+                    .map(PathBuf::from)
+                    .unwrap_or_else(default_notes_path),
+            }),
+            OutputSink::Stdout => Box::new(StdoutSink),
+            OutputSink::Webhook => Box::new(WebhookSink {
+                url: settings.webhook_url.clone().filter(|url| !url.trim().is_empty()),
+            }),
+        }
+    }
+}
+
+/// The configured outputs; none configured means typing the text.
+fn outputs(settings: &Settings) -> Vec<OutputSink> {
+    if settings.outputs.is_empty() {
+        vec![OutputSink::Inject]
+    } else {
+        settings.outputs.clone()
+    }
+}
+
+fn default_notes_path() -> PathBuf {
+    let mut path = config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("vwisper");
+    path.push("dictations.md");
+    path
+}
+
+/// Sends the dictation to every configured output. A failing output is
+/// reported to the UI and doesn't keep the others from running; the
+/// returned error names every output that failed.
+pub fn deliver(app: &AppHandle, transcript: &Transcript) -> Result<(), String> {
+    let mut failed = Vec::new();
+    for output in outputs(transcript.settings) {
+        let sink = output.sink(transcript.settings);
+        if let Err(e) = sink.write(app, transcript) {
+            report_error(app, sink.name(), &e);
+            failed.push(format!("{}: {}", sink.name(), e));
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Output failed ({})", failed.join("; ")))
+    }
+}
+
+fn report_error(app: &AppHandle, output: &str, error: &str) {
+    eprintln!("Output to {} failed: {}", output, error);
+    let _ = app.emit_to("main", "output-error", json!({ "output": output, "error": error }));
+}
+
+struct InjectSink;
+
+/// Where the inject output puts the text.
+#[derive(Debug, PartialEq, Eq)]
+enum Delivery {
+    Inject,
+    Clipboard,
+    Pending,
+}
+
+/// Decides where the text goes when the focus may have moved since
+/// recording started. When it is to be typed, brings the target back first.
+fn prepare_injection(focus: &dyn FocusBackend, target: Option<&ActiveWindow>, policy: FocusChangePolicy) -> Delivery {
+    let moved_on = target.is_some_and(|target| {
+        focus.capture().is_some_and(|current| !current.is_same_window(target) && !current.is_own_window())
+    });
+    if moved_on {
+        match policy {
+            FocusChangePolicy::Inject => {}
+            FocusChangePolicy::Clipboard => return Delivery::Clipboard,
+            FocusChangePolicy::Pending => return Delivery::Pending,
+        }
+    }
+
+    // Restore focus to the original window before injecting text
+    if let Some(window) = target {
+        if let Err(e) = focus.restore(window) {
+            eprintln!("Failed to restore focus: {}", e);
+        }
+        // Give the window a moment to gain focus
+        thread::sleep(Duration::from_millis(100));
+    }
+    Delivery::Inject
+}
+
+impl Sink for InjectSink {
+    fn name(&self) -> &'static str {
+        "inject"
+    }
+
+    /// Types the text into the target, unless the user has since moved to
+    /// another window; then `focus_change_policy` decides where it goes.
+    /// Failures are also reported through the injection events.
+    fn write(&self, app: &AppHandle, transcript: &Transcript) -> Result<(), String> {
+        let text = transcript.text;
+        let target = transcript.target;
+        match prepare_injection(focus::backend(), target, transcript.settings.focus_change_policy) {
+            Delivery::Inject => {}
+            Delivery::Clipboard => {
+                match textinjection::copy_to_clipboard(text) {
+                    Ok(()) => {
+                        let _ = app.emit_to("main", "injection-status", "clipboard");
+                        let _ = app.emit_to("main", "injection-notice", "You switched windows, so the text was copied to the clipboard instead");
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to copy text to the clipboard: {}", e);
+                        let _ = app.emit_to("main", "injection-status", "error");
+                        let _ = app.emit_to("main", "injection-error", &e.to_string());
+                        return Err(e.to_string());
+                    }
+                }
+                return Ok(());
+            }
+            Delivery::Pending => {
+                pending::hold(text.to_string());
+                let _ = app.emit_to("main", "injection-status", "pending");
+                let _ = app.emit_to("main", "pending-text", text);
+                return Ok(());
+            }
+        }
+
+        match textinjection::inject_text_with(text, &InjectionConfig::from_settings(transcript.settings)) {
+            Ok(injected) => {
+                undo::record(text, injected, target.cloned(), transcript.history_id.map(str::to_string));
+                let _ = app.emit_to("main", "injection-status", "success");
+            }
+            Err(e) => {
+                eprintln!("Text injection failed: {}", e);
+                let _ = app.emit_to("main", "injection-status", "error");
+                let _ = app.emit_to("main", "injection-error", &e.to_string());
+                return Err(e.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+struct ClipboardSink;
+
+impl Sink for ClipboardSink {
+    fn name(&self) -> &'static str {
+        "clipboard"
+    }
+
+    fn write(&self, app: &AppHandle, transcript: &Transcript) -> Result<(), String> {
+        textinjection::copy_to_clipboard(transcript.text).map_err(|e| e.to_string())?;
+        let _ = app.emit_to("main", "injection-status", "clipboard");
+        Ok(())
+    }
+}
+
+struct MarkdownSink {
+    path: PathBuf,
+}
+
+impl Sink for MarkdownSink {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn write(&self, _app: &AppHandle, transcript: &Transcript) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let mut heading = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        if let Some(app_name) = transcript.target.and_then(|w| w.process_name.as_deref()) {
+            heading.push_str(&format!(" · {}", app_name));
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to open {}: {}", self.path.display(), e))?;
+        write!(file, "## {}\n\n{}\n\n", heading, transcript.text.trim_end()).map_err(|e| e.to_string())
+    }
+}
+
+struct StdoutSink;
+
+impl Sink for StdoutSink {
+    fn name(&self) -> &'static str {
+        "stdout"
+    }
+
+    fn write(&self, _app: &AppHandle, transcript: &Transcript) -> Result<(), String> {
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}", transcript.text).and_then(|_| stdout.flush()).map_err(|e| e.to_string())
+    }
+}
+
+struct WebhookSink {
+    url: Option<String>,
+}
+
+impl Sink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    /// Posts from a background thread so a slow server doesn't hold up the
+    /// other outputs; a failure is reported to the UI when it happens.
+    fn write(&self, app: &AppHandle, transcript: &Transcript) -> Result<(), String> {
+        let url = self.url.clone().ok_or("No webhook URL configured")?;
+        let body = json!({
+            "text": transcript.text,
+            "timestamp": Utc::now().to_rfc3339(),
+            "language": transcript.language,
+            "action": transcript.action.as_str(),
+            "profile": transcript.profile,
+            "app_name": transcript.target.and_then(|w| w.process_name.as_deref()),
+            "window_title": transcript.target.and_then(|w| w.title.as_deref()),
+        });
+        let app = app.clone();
+        thread::spawn(move || {
+            if let Err(e) = post_webhook(&url, &body) {
+                report_error(&app, "webhook", &e);
+            }
+        });
+        Ok(())
+    }
+}
+
+fn post_webhook(url: &str, body: &serde_json::Value) -> Result<(), String> {
+    let client = Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = client.post(url).json(body).send().map_err(|e| format!("Webhook request failed: {}", e))?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("Webhook returned {}", status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::focus::FakeFocus;
+
+    fn window(handle: isize) -> ActiveWindow {
+        ActiveWindow { handle: Some(handle), ..Default::default() }
+    }
+
+    #[test]
+    fn restores_the_target_before_injecting() {
+        let focus = FakeFocus::focused(window(1));
+        assert_eq!(prepare_injection(&focus, Some(&window(1)), FocusChangePolicy::Clipboard), Delivery::Inject);
+        assert_eq!(focus.restored_handles(), vec![Some(1)]);
+    }
+
+    #[test]
+    fn without_a_target_types_into_whatever_has_focus() {
+        let focus = FakeFocus::focused(window(2));
+        assert_eq!(prepare_injection(&focus, None, FocusChangePolicy::Pending), Delivery::Inject);
+        assert!(focus.restored_handles().is_empty());
+    }
+
+    #[test]
+    fn moved_on_follows_the_policy() {
+        for (policy, expected) in [
+            (FocusChangePolicy::Clipboard, Delivery::Clipboard),
+            (FocusChangePolicy::Pending, Delivery::Pending),
+        ] {
+            let focus = FakeFocus::focused(window(2));
+            assert_eq!(prepare_injection(&focus, Some(&window(1)), policy), expected);
+            assert!(focus.restored_handles().is_empty());
+        }
+    }
+
+    #[test]
+    fn moved_on_with_inject_policy_brings_the_target_back() {
+        let focus = FakeFocus::focused(window(2));
+        assert_eq!(prepare_injection(&focus, Some(&window(1)), FocusChangePolicy::Inject), Delivery::Inject);
+        assert_eq!(focus.restored_handles(), vec![Some(1)]);
+    }
+
+    #[test]
+    fn our_own_window_does_not_count_as_moving_on() {
+        let own = ActiveWindow { handle: Some(9), pid: Some(std::process::id()), ..Default::default() };
+        let focus = FakeFocus::focused(own);
+        assert_eq!(prepare_injection(&focus, Some(&window(1)), FocusChangePolicy::Pending), Delivery::Inject);
+        assert_eq!(focus.restored_handles(), vec![Some(1)]);
+    }
+
+    #[test]
+    fn unknown_focus_does_not_count_as_moving_on() {
+        let focus = FakeFocus::default();
+        assert_eq!(prepare_injection(&focus, Some(&window(1)), FocusChangePolicy::Clipboard), Delivery::Inject);
+        assert_eq!(focus.restored_handles(), vec![Some(1)]);
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter};
+use crate::{focus, settings, undo};
+use crate::textinjection::{self, InjectionConfig};
+
+/// Text held back because the user switched windows while it was being
+/// transcribed. Only the latest dictation is kept.
+static PENDING: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn hold(text: String) {
+    *PENDING.lock().unwrap() = Some(text);
+}
+
+#[command]
+pub fn get_pending_text() -> Option<String> {
+    PENDING.lock().unwrap().clone()
+}
+
+#[command]
+pub fn clear_pending_text() {
+    PENDING.lock().unwrap().take();
+}
+
+/// Types the held text into the focused window and empties the slot. Bound
+/// to the pending-paste hotkey.
+#[command]
+pub fn paste_pending_text(app: AppHandle) -> Result<(), String> {
+    let Some(text) = PENDING.lock().unwrap().take() else {
+        return Ok(());
+    };
+    let config = settings::get_settings()
+        .map(|settings| InjectionConfig::from_settings(&settings))
+        .unwrap_or_default();
+    match textinjection::inject_text_with(&text, &config) {
+        Ok(injected) => {
+            undo::record(&text, injected, focus::capture_active_window(), None);
+            let _ = app.emit_to("main", "pending-text", Option::<String>::None);
+            Ok(())
+        }
+        Err(e) => {
+            // Keep it for another try.
+            hold(text);
+            Err(e.to_string())
+        }
+    }
+}
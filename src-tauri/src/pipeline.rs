@@ -0,0 +1,133 @@
+use crate::codemode;
+use crate::disfluency;
+use crate::formatting;
+use crate::normalization;
+use crate::replacements;
+use crate::normalization::{LanguageTable, NumberStyle};
+use crate::settings::Settings;
+
+/// Maps a configured language code or the language name Whisper reports in
+/// `verbose_json` ("english", "german", ...) to a two-letter code.
+pub fn language_code(language: Option<&str>) -> String {
+    let language = language.unwrap_or("en").trim().to_lowercase();
+    let code = match language.as_str() {
+        "english" => "en",
+        "german" => "de",
+        "french" => "fr",
+        "spanish" => "es",
+        other => other.split(['-', '_']).next().unwrap_or("en"),
+    };
+    if code.is_empty() {
+        "en".to_string()
+    } else {
+        code.to_string()
+    }
+}
+
+/// "Sounds good." -> "Sounds good". Multi-sentence text and ellipses keep
+/// their punctuation, since chat messages of several sentences read oddly
+/// with only the last period missing.
+fn strip_trailing_period(text: &str) -> String {
+    let trimmed = text.trim_end();
+    let Some(body) = trimmed.strip_suffix('.') else {
+        return text.to_string();
+    };
+    let single_sentence = !body.contains(['.', '?', '!', '\n']);
+    if single_sentence {
+        body.to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Identifiers want "max retries equals 5", not "five", so code mode
+/// always writes numbers as digits. There "minus" is the operator, left for
+/// code mode to turn into a symbol, not a sign: "y minus one" is `y - 1`.
+fn normalize_numbers(text: &str, settings: &Settings, language: &str) -> String {
+    // Languages without a number table are left as transcribed.
+    let Some(table) = normalization::table_for(language) else {
+        return text.to_string();
+    };
+    if settings.code_mode {
+        let table = LanguageTable { negative: &[], ..*table };
+        normalization::normalize(text, &table, NumberStyle::Digits)
+    } else {
+        normalization::normalize(text, table, settings.number_style)
+    }
+}
+
+/// Local, deterministic text stages run between transcription and injection.
+pub fn process(text: &str, settings: &Settings, detected_language: Option<&str>) -> String {
+    let language = language_code(settings.language.as_deref().or(detected_language));
+    let mut text = text.to_string();
+
+    if settings.disfluency_filter_enabled {
+        text = disfluency::clean(&text, &language);
+    }
+
+    if settings.number_normalization_enabled || settings.code_mode {
+        text = normalize_numbers(&text, settings, &language);
+    }
+
+    if settings.code_mode {
+        // Code mode has its own symbol table; prose commands such as
+        // "period" would clash with it.
+        text = codemode::apply(&text);
+    } else if settings.formatting_commands_enabled {
+        let commands = formatting::commands_for(settings, &language);
+        text = formatting::apply(&text, &commands, formatting::escape_word(&language));
+    }
+
+    if settings.strip_trailing_period {
+        text = strip_trailing_period(&text);
+    }
+
+    // Replacements run last so snippets are typed exactly as the user wrote them.
+    match replacements::load_rules() {
+        Ok(rules) => text = replacements::apply(&text, &rules),
+        Err(e) => eprintln!("Skipping replacements: {}", e),
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(text: &str) -> String {
+        let settings = Settings { code_mode: true, ..Default::default() };
+        codemode::apply(&normalize_numbers(text, &settings, "en"))
+    }
+
+    #[test]
+    fn code_mode_keeps_minus_as_an_operator() {
+        assert_eq!(code("x equals y minus one"), "x = y - 1");
+        assert_eq!(code("count minus equals two"), "count -= 2");
+    }
+
+    #[test]
+    fn code_mode_writes_small_numbers_as_digits() {
+        assert_eq!(code("max retries equals three"), "max retries = 3");
+    }
+
+    #[test]
+    fn prose_keeps_negative_numbers() {
+        let settings = Settings { number_style: NumberStyle::Digits, ..Default::default() };
+        assert_eq!(normalize_numbers("it was minus four degrees", &settings, "en"), "it was -4°");
+    }
+
+    #[test]
+    fn strips_the_period_of_a_single_sentence() {
+        assert_eq!(strip_trailing_period("Sounds good."), "Sounds good");
+        assert_eq!(strip_trailing_period("One. Two."), "One. Two.");
+        assert_eq!(strip_trailing_period("Wait..."), "Wait...");
+    }
+
+    #[test]
+    fn maps_language_names_to_codes() {
+        assert_eq!(language_code(Some("german")), "de");
+        assert_eq!(language_code(Some("en-US")), "en");
+        assert_eq!(language_code(None), "en");
+    }
+}
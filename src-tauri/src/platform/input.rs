@@ -0,0 +1,228 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use crate::hotkey::{self, Key};
+use crate::keystate::KeyStateMachine;
+use crate::{audio, handle_key_action, pending, undo, StartedRecording};
+
+/// A key or mouse button going down or up, stamped when the hook saw it.
+#[derive(Debug, Clone, Copy)]
+pub struct InputEvent {
+    pub key: Key,
+    pub down: bool,
+    pub at: Instant,
+}
+
+impl InputEvent {
+    pub fn now(key: Key, down: bool) -> Self {
+        Self { key, down, at: Instant::now() }
+    }
+
+    /// Converts an rdev hook event; `None` for keys the hotkey can't use
+    /// and for mouse moves.
+    pub fn from_rdev(event: &rdev::EventType) -> Option<Self> {
+        use rdev::EventType;
+
+        let (key, down) = match event {
+            EventType::KeyPress(key) => (hotkey::from_native_name(&format!("{:?}", key))?, true),
+            EventType::KeyRelease(key) => (hotkey::from_native_name(&format!("{:?}", key))?, false),
+            EventType::ButtonPress(button) => (hotkey::from_rdev_button(button)?, true),
+            EventType::ButtonRelease(button) => (hotkey::from_rdev_button(button)?, false),
+            _ => return None,
+        };
+        Some(Self::now(key, down))
+    }
+}
+
+/// Starts the thread that owns the key state machine and returns the sender
+/// the input hooks feed. Hooks must return quickly, so they only forward
+/// events; all the work happens here.
+pub fn start_dispatcher(app_handle: AppHandle) -> Sender<InputEvent> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || run_dispatcher(app_handle, receiver));
+    sender
+}
+
+/// Installs rdev's global hook: a low-level keyboard and mouse hook on
+/// Windows, an event tap on macOS (needs the Input Monitoring permission)
+/// and XRecord on X11.
+pub fn start_rdev_listener(sender: Sender<InputEvent>) {
+    thread::spawn(move || {
+        let result = rdev::listen(move |event| {
+            if let Some(input) = InputEvent::from_rdev(&event.event_type) {
+                let _ = sender.send(input);
+            }
+        });
+        if let Err(e) = result {
+            eprintln!("Failed to start global key hook: {:?}", e);
+        }
+    });
+}
+
+/// With no input for this long between recordings, keys still marked as
+/// held are assumed to have had their release lost, e.g. to a locked
+/// screen or a secure desktop that the hook can't see.
+const STALE_KEYS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Records `event` in `held_keys`, kept in the order the keys went down.
+/// Returns false when the hook must have missed releases: a key that is
+/// already held goes down again without being the newest key, which
+/// auto-repeat never does. `held_keys` then starts over from this event.
+fn track_key(held_keys: &mut Vec<Key>, event: &InputEvent) -> bool {
+    let in_sync = !event.down || held_keys.last() == Some(&event.key) || !held_keys.contains(&event.key);
+    if !in_sync {
+        held_keys.clear();
+    }
+    held_keys.retain(|held| *held != event.key);
+    if event.down {
+        held_keys.push(event.key);
+    }
+    in_sync
+}
+
+fn run_dispatcher(app_handle: AppHandle, receiver: Receiver<InputEvent>) {
+    let config = hotkey::active();
+    let mut machine = KeyStateMachine::new(config.mode, config.min_hold);
+    // Every key and button currently held, across all input sources.
+    let mut held_keys: Vec<Key> = Vec::new();
+    let mut recording: Option<StartedRecording> = None;
+    // Index into `config.bindings` of the hotkey the machine is following.
+    let mut binding = 0;
+    let mut pending_paste_down = false;
+    let mut undo_down = false;
+    let mut last_input = Instant::now();
+
+    loop {
+        // Wake up at the debounce deadline so a release isn't left pending
+        // until the next key event, and to drop stale keys.
+        let stale_at = (machine.is_idle() && !held_keys.is_empty()).then(|| last_input + STALE_KEYS_TIMEOUT);
+        let event = match machine.deadline().into_iter().chain(stale_at).min() {
+            Some(deadline) => match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(event) => Some(event),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return,
+            },
+            None => match receiver.recv() {
+                Ok(event) => Some(event),
+                Err(_) => return,
+            },
+        };
+        let in_sync = match &event {
+            Some(event) => track_key(&mut held_keys, event),
+            None => stale_at.is_none_or(|at| Instant::now() < at),
+        };
+        if !in_sync {
+            eprintln!("Key releases were missed, forgetting held keys");
+            if event.is_none() {
+                held_keys.clear();
+            }
+            // Their releases are lost too, so they must not fire.
+            pending_paste_down = false;
+            undo_down = false;
+        }
+        let now = match event {
+            Some(event) => {
+                last_input = event.at;
+                event.at
+            }
+            None => Instant::now(),
+        };
+
+        // Read on every event so a settings change applies immediately.
+        let config = hotkey::active();
+        machine.configure(config.mode, config.min_hold);
+
+        // A hands-free recording may have been stopped from the UI.
+        if machine.is_recording() && !audio::is_recording() {
+            machine.reset();
+        }
+
+        // Paste on release, so the hotkey's modifiers don't mangle the text.
+        let pending_paste_pressed = config.pending_paste.as_ref().is_some_and(|hotkey| hotkey.is_pressed(&held_keys));
+        if pending_paste_down && !pending_paste_pressed && machine.is_idle() {
+            let app_handle = app_handle.clone();
+            thread::spawn(move || {
+                if let Err(e) = pending::paste_pending_text(app_handle) {
+                    eprintln!("Failed to paste pending text: {}", e);
+                }
+            });
+        }
+        pending_paste_down = pending_paste_pressed;
+
+        let undo_pressed = config.undo.as_ref().is_some_and(|hotkey| hotkey.is_pressed(&held_keys));
+        if undo_down && !undo_pressed && machine.is_idle() {
+            let app_handle = app_handle.clone();
+            thread::spawn(move || {
+                if let Err(e) = undo::undo_last_dictation(app_handle) {
+                    eprintln!("Failed to undo the last dictation: {}", e);
+                }
+            });
+        }
+        undo_down = undo_pressed;
+
+        // A recording stays with the hotkey that started it; between
+        // recordings, follow whichever hotkey is pressed.
+        if machine.is_idle() {
+            if let Some(pressed) = config.bindings.iter().position(|(hotkey, _)| hotkey.is_pressed(&held_keys)) {
+                binding = pressed;
+            }
+        }
+        let Some((hotkey, dictation)) = config.bindings.get(binding).or(config.bindings.first()) else {
+            continue;
+        };
+
+        let cancel_pressed = config.cancel_key.is_some_and(|key| held_keys.iter().any(|held| key.matches(held)));
+        let action = machine.update(
+            hotkey.is_pressed(&held_keys),
+            hotkey.has_other_keys(&held_keys),
+            cancel_pressed,
+            now,
+        );
+        if let Some(action) = action {
+            handle_key_action(&app_handle, action, dictation, &mut recording);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotkey::Side;
+
+    fn track(held_keys: &mut Vec<Key>, key: Key, down: bool) -> bool {
+        track_key(held_keys, &InputEvent::now(key, down))
+    }
+
+    #[test]
+    fn tracks_presses_and_releases_in_order() {
+        let mut held = Vec::new();
+        assert!(track(&mut held, Key::Ctrl(Side::Left), true));
+        assert!(track(&mut held, Key::Space, true));
+        assert_eq!(held, vec![Key::Ctrl(Side::Left), Key::Space]);
+        assert!(track(&mut held, Key::Ctrl(Side::Left), false));
+        assert_eq!(held, vec![Key::Space]);
+        // A release for a key that isn't held is harmless.
+        assert!(track(&mut held, Key::Escape, false));
+        assert_eq!(held, vec![Key::Space]);
+    }
+
+    #[test]
+    fn auto_repeat_of_the_newest_key_is_in_sync() {
+        let mut held = Vec::new();
+        track(&mut held, Key::Ctrl(Side::Left), true);
+        track(&mut held, Key::Space, true);
+        assert!(track(&mut held, Key::Space, true));
+        assert_eq!(held, vec![Key::Ctrl(Side::Left), Key::Space]);
+    }
+
+    #[test]
+    fn a_repeated_press_of_an_older_key_drops_stale_keys() {
+        let mut held = Vec::new();
+        // Ctrl+L's releases were lost to the lock screen.
+        track(&mut held, Key::Ctrl(Side::Left), true);
+        track(&mut held, Key::Char('l'), true);
+        assert!(!track(&mut held, Key::Ctrl(Side::Left), true));
+        assert_eq!(held, vec![Key::Ctrl(Side::Left)]);
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::mpsc::Sender;
+use std::thread;
+use tauri::AppHandle;
+use crate::hotkey;
+use crate::platform::input::{self, InputEvent};
+
+/// The kind of graphical session. X11 lets any client observe and synthesize
+/// input; Wayland compositors do not, so hotkeys are read from evdev and text
+/// is typed through helper tools instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinuxSession {
+    X11,
+    Wayland,
+}
+
+impl LinuxSession {
+    pub fn detect() -> Self {
+        match std::env::var("XDG_SESSION_TYPE").unwrap_or_default().to_lowercase().as_str() {
+            "wayland" => LinuxSession::Wayland,
+            "x11" => LinuxSession::X11,
+            // Sessions started without logind (e.g. from a TTY) may not set it.
+            _ if std::env::var_os("WAYLAND_DISPLAY").is_some() => LinuxSession::Wayland,
+            _ => LinuxSession::X11,
+        }
+    }
+}
+
+pub fn start_global_key_monitor(app_handle: AppHandle) {
+    let sender = input::start_dispatcher(app_handle);
+    match LinuxSession::detect() {
+        LinuxSession::X11 => input::start_rdev_listener(sender),
+        LinuxSession::Wayland => start_evdev_monitor(sender),
+    }
+}
+
+/// Wayland: the compositor hides global key events from clients, so read the
+/// keyboards directly. This needs read access to /dev/input/event*, usually
+/// by adding the user to the `input` group.
+fn start_evdev_monitor(sender: Sender<InputEvent>) {
+    use evdev::InputEventKind;
+
+    // Mice too, so side buttons work; the hotkey can change at runtime, so
+    // don't filter on the keys it currently uses.
+    let keyboards: Vec<_> = evdev::enumerate()
+        .map(|(_, device)| device)
+        .filter(|device| device.supported_keys().is_some())
+        .collect();
+    if keyboards.is_empty() {
+        // XWayland still reports keys while one of its windows has focus.
+        eprintln!("No readable keyboard devices found; add your user to the 'input' group to use the hotkey on Wayland");
+        input::start_rdev_listener(sender);
+        return;
+    }
+
+    for mut device in keyboards {
+        let sender = sender.clone();
+        let name = device.name().unwrap_or("keyboard").to_string();
+        thread::spawn(move || loop {
+            let events = match device.fetch_events() {
+                Ok(events) => events,
+                Err(e) => {
+                    // The device was unplugged.
+                    eprintln!("Stopped reading {}: {}", name, e);
+                    return;
+                }
+            };
+            for event in events {
+                let InputEventKind::Key(key) = event.kind() else { continue };
+                let Some(key) = hotkey::from_native_name(&format!("{:?}", key)) else { continue };
+                // 1 = press, 0 = release, 2 = autorepeat.
+                let down = match event.value() {
+                    1 => true,
+                    0 => false,
+                    _ => continue,
+                };
+                if sender.send(InputEvent::now(key, down)).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+}
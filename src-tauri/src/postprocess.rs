@@ -0,0 +1,102 @@
+use std::time::Duration;
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use crate::settings::Settings;
+
+const DEFAULT_LLM_BASE_URL: &str = "https://api.groq.com/openai/v1";
+const DEFAULT_LLM_MODEL: &str = "llama-3.1-8b-instant";
+const DEFAULT_LLM_TIMEOUT_MS: u64 = 5000;
+
+pub const DEFAULT_SYSTEM_PROMPT: &str = "You clean up dictated text. Fix punctuation, capitalization and run-on sentences, and remove filler words such as \"um\" and \"uh\". Keep the speaker's wording and language otherwise unchanged. Reply with the cleaned text only, without quotes or commentary.";
+
+/// Used by the command action: the dictation is a request, not text to tidy.
+pub const COMMAND_SYSTEM_PROMPT: &str = "The user dictates an instruction, such as a message to write or text to produce. Carry it out and reply with only the resulting text, ready to be typed into the user's application, without quotes, preamble or commentary. Answer in the language the instruction was spoken in.";
+
+/// A chat-completions endpoint used to tidy up raw Whisper output. Any
+/// OpenAI-compatible server works: Groq, OpenAI, or a local llama.cpp/Ollama.
+#[derive(Debug, Clone)]
+pub struct LlmConfig {
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub system_prompt: String,
+    pub timeout: Duration,
+}
+
+impl LlmConfig {
+    /// Returns `None` when the cleanup pass is disabled. Code mode skips it
+    /// too, since a chat model would "fix" identifiers and symbols.
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        if !settings.llm_cleanup_enabled || settings.code_mode {
+            return None;
+        }
+        let base_url = settings
+            .llm_endpoint
+            .clone()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_LLM_BASE_URL.to_string());
+        // Reuse the Groq key when the user hasn't configured a separate one.
+        let api_key = settings
+            .llm_api_key
+            .clone()
+            .filter(|key| !key.trim().is_empty())
+            .or_else(|| {
+                if base_url.starts_with(DEFAULT_LLM_BASE_URL) {
+                    settings.groq_api_key.clone()
+                } else {
+                    None
+                }
+            });
+        Some(Self {
+            base_url,
+            api_key,
+            model: settings
+                .llm_model
+                .clone()
+                .filter(|model| !model.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_LLM_MODEL.to_string()),
+            system_prompt: settings
+                .llm_system_prompt
+                .clone()
+                .filter(|prompt| !prompt.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_SYSTEM_PROMPT.to_string()),
+            timeout: Duration::from_millis(settings.llm_timeout_ms.unwrap_or(DEFAULT_LLM_TIMEOUT_MS)),
+        })
+    }
+}
+
+/// Sends `text` through the configured model and returns the cleaned version.
+/// Callers are expected to fall back to the raw text on error.
+pub fn clean_up(text: &str, config: &LlmConfig) -> Result<String, String> {
+    let client = Client::builder()
+        .timeout(config.timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+    let body = json!({
+        "model": config.model,
+        "temperature": 0,
+        "messages": [
+            { "role": "system", "content": config.system_prompt },
+            { "role": "user", "content": text },
+        ],
+    });
+
+    let mut request = client.post(&url).json(&body);
+    if let Some(api_key) = &config.api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let resp = request.send().map_err(|e| format!("LLM request failed: {}", e))?;
+    let status = resp.status();
+    let text = resp.text().map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("LLM API error: {} - {}", status, text));
+    }
+
+    let v: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let cleaned = v["choices"][0]["message"]["content"].as_str().unwrap_or("").trim();
+    if cleaned.is_empty() {
+        return Err("LLM returned an empty response".to_string());
+    }
+    Ok(cleaned.to_string())
+}
@@ -0,0 +1,330 @@
+use std::fs;
+use std::path::PathBuf;
+use dirs::config_dir;
+use serde::{Deserialize, Serialize};
+use tauri::command;
+use uuid::Uuid;
+use crate::focus::{self, ActiveWindow};
+use crate::output::OutputSink;
+use crate::settings::Settings;
+use crate::textinjection::InjectionMethod;
+
+/// Settings applied while dictating into particular applications, e.g. code
+/// mode in the editor or no trailing period in chat. Every override is
+/// optional; `None` keeps the global setting.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Process names such as "code" or "slack.exe", compared without path,
+    /// extension or case. X11 window classes and macOS bundle identifiers
+    /// match too.
+    #[serde(default)]
+    pub match_process: Vec<String>,
+    /// Case-insensitive substring of the window title, e.g. "Gmail" for a
+    /// browser tab.
+    #[serde(default)]
+    pub match_title: Option<String>,
+    #[serde(default)]
+    pub transcription_provider: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub translate_mode: Option<bool>,
+    #[serde(default)]
+    pub llm_cleanup_enabled: Option<bool>,
+    #[serde(default)]
+    pub llm_system_prompt: Option<String>,
+    #[serde(default)]
+    pub disfluency_filter_enabled: Option<bool>,
+    #[serde(default)]
+    pub number_normalization_enabled: Option<bool>,
+    #[serde(default)]
+    pub formatting_commands_enabled: Option<bool>,
+    #[serde(default)]
+    pub code_mode: Option<bool>,
+    #[serde(default)]
+    pub strip_trailing_period: Option<bool>,
+    #[serde(default)]
+    pub injection_method: Option<InjectionMethod>,
+    #[serde(default)]
+    pub injection_key_delay_ms: Option<u64>,
+    #[serde(default)]
+    pub hybrid_paste_threshold: Option<usize>,
+    /// Where dictations into this application go, e.g. only the notes file.
+    #[serde(default)]
+    pub outputs: Option<Vec<OutputSink>>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ProfilesData {
+    profiles: Vec<Profile>,
+}
+
+impl Profile {
+    /// A profile needs at least one criterion; one with only a title pattern
+    /// matches that title in any application.
+    pub fn matches(&self, window: &ActiveWindow) -> bool {
+        let match_title = self.match_title.as_deref().map(str::trim).filter(|t| !t.is_empty());
+        if !self.enabled || (self.match_process.is_empty() && match_title.is_none()) {
+            return false;
+        }
+        let process_ok = self.match_process.is_empty()
+            || window.process_name.as_deref().is_some_and(|name| {
+                let name = focus::normalize_process_name(name);
+                self.match_process
+                    .iter()
+                    .any(|p| focus::normalize_process_name(p).eq_ignore_ascii_case(&name))
+            })
+            || [&window.window_class, &window.bundle_id]
+                .into_iter()
+                .flatten()
+                .any(|id| self.match_process.iter().any(|p| p.trim().eq_ignore_ascii_case(id)));
+        let title_ok = match match_title {
+            Some(pattern) => window
+                .title
+                .as_deref()
+                .is_some_and(|title| title.to_lowercase().contains(&pattern.to_lowercase())),
+            None => true,
+        };
+        process_ok && title_ok
+    }
+
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(val) = &self.transcription_provider {
+            settings.transcription_provider = Some(val.clone());
+        }
+        if let Some(val) = &self.language {
+            settings.language = Some(val.clone()).filter(|l| !l.trim().is_empty());
+        }
+        if let Some(val) = self.translate_mode {
+            settings.translate_mode = val;
+        }
+        if let Some(val) = self.llm_cleanup_enabled {
+            settings.llm_cleanup_enabled = val;
+        }
+        if let Some(val) = &self.llm_system_prompt {
+            settings.llm_system_prompt = Some(val.clone());
+        }
+        if let Some(val) = self.disfluency_filter_enabled {
+            settings.disfluency_filter_enabled = val;
+        }
+        if let Some(val) = self.number_normalization_enabled {
+            settings.number_normalization_enabled = val;
+        }
+        if let Some(val) = self.formatting_commands_enabled {
+            settings.formatting_commands_enabled = val;
+        }
+        if let Some(val) = self.code_mode {
+            settings.code_mode = val;
+        }
+        if let Some(val) = self.strip_trailing_period {
+            settings.strip_trailing_period = val;
+        }
+        if let Some(val) = self.injection_method {
+            settings.injection_method = val;
+        }
+        if let Some(val) = self.injection_key_delay_ms {
+            settings.injection_key_delay_ms = Some(val);
+        }
+        if let Some(val) = self.hybrid_paste_threshold {
+            settings.hybrid_paste_threshold = Some(val);
+        }
+        if let Some(val) = &self.outputs {
+            settings.outputs = val.clone();
+        }
+    }
+}
+
+fn profiles_path() -> PathBuf {
+    let mut path = config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("vwisper");
+    fs::create_dir_all(&path).ok();
+    path.push("profiles.json");
+    path
+}
+
+/// The saved profiles; none when the file doesn't exist yet. A file that
+/// can't be parsed is an error so that saving doesn't overwrite it.
+pub fn load_profiles() -> Result<Vec<Profile>, String> {
+    let path = profiles_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str::<ProfilesData>(&data)
+        .map(|d| d.profiles)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn save_profiles(profiles: Vec<Profile>) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(&ProfilesData { profiles }).map_err(|e| e.to_string())?;
+    fs::write(profiles_path(), data).map_err(|e| e.to_string())
+}
+
+/// The first enabled profile matching `window`, in the user's order.
+pub fn find_profile(window: &ActiveWindow) -> Option<Profile> {
+    let profiles = load_profiles().unwrap_or_else(|e| {
+        eprintln!("Ignoring profiles: {}", e);
+        Vec::new()
+    });
+    profiles.into_iter().find(|p| p.matches(window))
+}
+
+/// The global settings with the matching profile's overrides applied, plus
+/// the name of that profile.
+pub fn settings_for(settings: &Settings, window: Option<&ActiveWindow>) -> (Settings, Option<String>) {
+    let mut settings = settings.clone();
+    let profile = window.and_then(find_profile);
+    if let Some(profile) = &profile {
+        profile.apply(&mut settings);
+    }
+    (settings, profile.map(|p| p.name))
+}
+
+#[command]
+pub fn get_profiles() -> Result<Vec<Profile>, String> {
+    load_profiles()
+}
+
+/// Adds a new profile, or updates the existing profile with the same id.
+#[command]
+pub fn save_profile(mut profile: Profile) -> Result<Profile, String> {
+    if profile.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    profile.match_title = profile.match_title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let mut profiles = load_profiles()?;
+    if profile.id.is_empty() {
+        profile.id = Uuid::new_v4().to_string();
+    }
+    match profiles.iter_mut().find(|p| p.id == profile.id) {
+        Some(existing) => *existing = profile.clone(),
+        None => profiles.push(profile.clone()),
+    }
+    save_profiles(profiles)?;
+    Ok(profile)
+}
+
+#[command]
+pub fn delete_profile(id: String) -> Result<(), String> {
+    let mut profiles = load_profiles()?;
+    profiles.retain(|p| p.id != id);
+    save_profiles(profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(match_process: &[&str], match_title: Option<&str>) -> Profile {
+        Profile {
+            name: "Test".to_string(),
+            enabled: true,
+            match_process: match_process.iter().map(|p| p.to_string()).collect(),
+            match_title: match_title.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn window(process_name: &str, title: &str) -> ActiveWindow {
+        ActiveWindow {
+            process_name: Some(process_name.to_string()),
+            title: Some(title.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn process_names_ignore_path_extension_and_case() {
+        let editor = window("Code", "main.rs");
+        assert!(profile(&["code"], None).matches(&editor));
+        assert!(profile(&["Code.exe"], None).matches(&editor));
+        assert!(profile(&[r"C:\Program Files\VS Code\Code.EXE"], None).matches(&editor));
+        assert!(profile(&["/usr/share/code/code"], None).matches(&editor));
+        assert!(profile(&["slack", "code"], None).matches(&editor));
+        assert!(!profile(&["slack"], None).matches(&editor));
+    }
+
+    #[test]
+    fn window_class_and_bundle_id_match() {
+        let x11 = ActiveWindow { window_class: Some("Google-chrome".to_string()), ..Default::default() };
+        assert!(profile(&["google-chrome"], None).matches(&x11));
+        let mac = ActiveWindow { bundle_id: Some("com.tinyspeck.slackmacgap".to_string()), ..Default::default() };
+        assert!(profile(&[" com.tinyspeck.slackmacgap "], None).matches(&mac));
+        assert!(!profile(&["com.apple.mail"], None).matches(&mac));
+    }
+
+    #[test]
+    fn title_is_a_case_insensitive_substring() {
+        let gmail = window("chrome", "Inbox - Gmail - Google Chrome");
+        assert!(profile(&[], Some("gmail")).matches(&gmail));
+        assert!(profile(&["chrome"], Some("Gmail")).matches(&gmail));
+        assert!(!profile(&["firefox"], Some("Gmail")).matches(&gmail));
+        assert!(!profile(&[], Some("Outlook")).matches(&gmail));
+        assert!(!profile(&[], Some("gmail")).matches(&ActiveWindow::default()));
+    }
+
+    #[test]
+    fn blank_title_is_no_criterion() {
+        let gmail = window("chrome", "Inbox - Gmail");
+        assert!(profile(&["chrome"], Some("  ")).matches(&gmail));
+        assert!(!profile(&[], Some("  ")).matches(&gmail));
+    }
+
+    #[test]
+    fn disabled_profiles_never_match() {
+        let mut disabled = profile(&["code"], None);
+        disabled.enabled = false;
+        assert!(!disabled.matches(&window("code", "")));
+    }
+
+    #[test]
+    fn profile_without_criteria_matches_nothing() {
+        assert!(!profile(&[], None).matches(&window("code", "main.rs")));
+        assert!(!profile(&[], None).matches(&ActiveWindow::default()));
+    }
+
+    #[test]
+    fn apply_overrides_only_what_is_set() {
+        let mut settings = Settings {
+            language: Some("de".to_string()),
+            strip_trailing_period: true,
+            disfluency_filter_enabled: true,
+            injection_key_delay_ms: Some(5),
+            ..Default::default()
+        };
+        let overrides = Profile {
+            code_mode: Some(true),
+            strip_trailing_period: Some(false),
+            injection_method: Some(InjectionMethod::Type),
+            outputs: Some(vec![OutputSink::Markdown]),
+            ..profile(&["code"], None)
+        };
+        overrides.apply(&mut settings);
+
+        assert!(settings.code_mode);
+        assert!(!settings.strip_trailing_period);
+        assert_eq!(settings.injection_method, InjectionMethod::Type);
+        assert_eq!(settings.outputs, vec![OutputSink::Markdown]);
+        // Untouched.
+        assert_eq!(settings.language.as_deref(), Some("de"));
+        assert!(settings.disfluency_filter_enabled);
+        assert_eq!(settings.injection_key_delay_ms, Some(5));
+    }
+
+    #[test]
+    fn blank_language_override_means_detect() {
+        let mut settings = Settings { language: Some("de".to_string()), ..Default::default() };
+        let overrides = Profile { language: Some(" ".to_string()), ..profile(&["code"], None) };
+        overrides.apply(&mut settings);
+        assert_eq!(settings.language, None);
+    }
+}
@@ -0,0 +1,286 @@
+use std::fs;
+use std::path::PathBuf;
+use dirs::config_dir;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::command;
+use uuid::Uuid;
+
+/// How a rule's `pattern` is matched against the transcript.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// The pattern anywhere in the text, e.g. inside other words.
+    Literal,
+    /// The pattern only as a separate word or phrase.
+    #[default]
+    WholeWord,
+    /// A regular expression; the replacement may use `$1` / `${name}`.
+    Regex,
+}
+
+/// A text replacement or snippet, e.g. "my email" -> "me@example.com".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplacementRule {
+    #[serde(default)]
+    pub id: String,
+    pub pattern: String,
+    pub replacement: String,
+    #[serde(default)]
+    pub kind: RuleKind,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RulesData {
+    rules: Vec<ReplacementRule>,
+}
+
+impl ReplacementRule {
+    fn compile(&self) -> Result<Regex, String> {
+        let pattern = match self.kind {
+            RuleKind::Literal => regex::escape(&self.pattern),
+            RuleKind::WholeWord => {
+                // \b only makes sense next to word characters, so "C++" still
+                // matches at its end.
+                let escaped = regex::escape(&self.pattern);
+                let start = if self.pattern.starts_with(|c: char| c.is_alphanumeric() || c == '_') { r"\b" } else { "" };
+                let end = if self.pattern.ends_with(|c: char| c.is_alphanumeric() || c == '_') { r"\b" } else { "" };
+                format!("{}{}{}", start, escaped, end)
+            }
+            RuleKind::Regex => self.pattern.clone(),
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(self.case_insensitive)
+            .build()
+            .map_err(|e| format!("Invalid pattern '{}': {}", self.pattern, e))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.pattern.is_empty() {
+            return Err("Replacement pattern cannot be empty".to_string());
+        }
+        self.compile().map(|_| ())
+    }
+}
+
+fn rules_path() -> PathBuf {
+    let mut path = config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("vwisper");
+    fs::create_dir_all(&path).ok();
+    path.push("replacements.json");
+    path
+}
+
+/// The saved rules; none when the file doesn't exist yet. A file that
+/// can't be parsed is an error so that saving doesn't overwrite it.
+pub fn load_rules() -> Result<Vec<ReplacementRule>, String> {
+    let path = rules_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str::<RulesData>(&data)
+        .map(|d| d.rules)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn save_rules(rules: Vec<ReplacementRule>) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(&RulesData { rules }).map_err(|e| e.to_string())?;
+    fs::write(rules_path(), data).map_err(|e| e.to_string())
+}
+
+/// Applies every enabled rule in order, each one to the output of the last.
+pub fn apply(text: &str, rules: &[ReplacementRule]) -> String {
+    let mut text = text.to_string();
+    for rule in rules.iter().filter(|r| r.enabled) {
+        let regex = match rule.compile() {
+            Ok(regex) => regex,
+            Err(e) => {
+                eprintln!("Skipping replacement rule: {}", e);
+                continue;
+            }
+        };
+        text = match rule.kind {
+            RuleKind::Regex => regex.replace_all(&text, rule.replacement.as_str()).into_owned(),
+            _ => regex.replace_all(&text, NoExpand(&rule.replacement)).into_owned(),
+        };
+    }
+    text
+}
+
+/// Parses either `{ "rules": [...] }` as written by export or a bare array.
+fn parse_rules(data: &str) -> Result<Vec<ReplacementRule>, String> {
+    serde_json::from_str::<RulesData>(data)
+        .map(|d| d.rules)
+        .or_else(|_| serde_json::from_str::<Vec<ReplacementRule>>(data))
+        .map_err(|e| format!("Invalid replacement rules file: {}", e))
+}
+
+#[command]
+pub fn get_replacement_rules() -> Result<Vec<ReplacementRule>, String> {
+    load_rules()
+}
+
+/// Adds a new rule, or updates the existing rule with the same id.
+#[command]
+pub fn save_replacement_rule(mut rule: ReplacementRule) -> Result<ReplacementRule, String> {
+    rule.validate()?;
+    let mut rules = load_rules()?;
+    if rule.id.is_empty() {
+        rule.id = Uuid::new_v4().to_string();
+    }
+    match rules.iter_mut().find(|r| r.id == rule.id) {
+        Some(existing) => *existing = rule.clone(),
+        None => rules.push(rule.clone()),
+    }
+    save_rules(rules)?;
+    Ok(rule)
+}
+
+#[command]
+pub fn delete_replacement_rule(id: String) -> Result<(), String> {
+    let mut rules = load_rules()?;
+    rules.retain(|r| r.id != id);
+    save_rules(rules)
+}
+
+/// Imports rules from a JSON file, appending them to the current rules or
+/// replacing them when `replace_existing` is set.
+#[command]
+pub fn import_replacement_rules(path: String, replace_existing: bool) -> Result<usize, String> {
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let imported = parse_rules(&data)?;
+    for rule in &imported {
+        rule.validate()?;
+    }
+
+    let mut rules = if replace_existing { Vec::new() } else { load_rules()? };
+    // Ids must stay unique, also among the imported rules themselves.
+    let count = imported.len();
+    for mut rule in imported {
+        if rule.id.is_empty() || rules.iter().any(|r| r.id == rule.id) {
+            rule.id = Uuid::new_v4().to_string();
+        }
+        rules.push(rule);
+    }
+    save_rules(rules)?;
+    Ok(count)
+}
+
+#[command]
+pub fn export_replacement_rules(path: String) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(&RulesData { rules: load_rules()? }).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: &str, kind: RuleKind) -> ReplacementRule {
+        ReplacementRule {
+            id: String::new(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            kind,
+            case_insensitive: false,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn whole_word_matches_only_separate_words() {
+        let rules = [rule("cat", "dog", RuleKind::WholeWord)];
+        assert_eq!(apply("cat catalog bobcat cat.", &rules), "dog catalog bobcat dog.");
+    }
+
+    #[test]
+    fn whole_word_patterns_may_end_in_symbols() {
+        let rules = [rule("C++", "cpp", RuleKind::WholeWord)];
+        assert_eq!(apply("I like C++ and C++.", &rules), "I like cpp and cpp.");
+        let rules = [rule(".net", "dotnet", RuleKind::WholeWord)];
+        assert_eq!(apply("use .net today", &rules), "use dotnet today");
+    }
+
+    #[test]
+    fn literal_matches_inside_words() {
+        let rules = [rule("cat", "dog", RuleKind::Literal)];
+        assert_eq!(apply("catalog bobcat", &rules), "dogalog bobdog");
+    }
+
+    #[test]
+    fn case_insensitive_matching() {
+        let mut insensitive = rule("my email", "me@example.com", RuleKind::WholeWord);
+        insensitive.case_insensitive = true;
+        assert_eq!(apply("Send it to My Email.", &[insensitive]), "Send it to me@example.com.");
+        let sensitive = rule("my email", "me@example.com", RuleKind::WholeWord);
+        assert_eq!(apply("Send it to My Email.", &[sensitive]), "Send it to My Email.");
+    }
+
+    #[test]
+    fn regex_rules_expand_groups() {
+        let rules = [rule(r"ticket (\d+)", "#$1", RuleKind::Regex)];
+        assert_eq!(apply("see ticket 42", &rules), "see #42");
+        let rules = [rule(r"(?P<user>\w+) at example", "${user}@example.com", RuleKind::Regex)];
+        assert_eq!(apply("bob at example", &rules), "bob@example.com");
+    }
+
+    #[test]
+    fn other_rules_insert_dollars_literally() {
+        let rules = [rule("price", "$1 each", RuleKind::WholeWord)];
+        assert_eq!(apply("price", &rules), "$1 each");
+        let rules = [rule("usd", "$1", RuleKind::Literal)];
+        assert_eq!(apply("usd", &rules), "$1");
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped() {
+        let mut disabled = rule("cat", "dog", RuleKind::WholeWord);
+        disabled.enabled = false;
+        assert_eq!(apply("cat", &[disabled]), "cat");
+    }
+
+    #[test]
+    fn rules_that_fail_to_compile_are_skipped() {
+        let rules = [rule("(unclosed", "x", RuleKind::Regex), rule("cat", "dog", RuleKind::WholeWord)];
+        assert_eq!(apply("cat (unclosed", &rules), "dog (unclosed");
+        assert!(rules[0].validate().is_err());
+    }
+
+    #[test]
+    fn rules_apply_in_order() {
+        let rules = [rule("a", "b", RuleKind::WholeWord), rule("b", "c", RuleKind::WholeWord)];
+        assert_eq!(apply("a", &rules), "c");
+    }
+
+    #[test]
+    fn empty_patterns_are_invalid() {
+        assert!(rule("", "x", RuleKind::Literal).validate().is_err());
+    }
+
+    #[test]
+    fn parses_both_import_formats() {
+        let wrapped = r#"{ "rules": [{ "id": "1", "pattern": "a", "replacement": "b" }] }"#;
+        let rules = parse_rules(wrapped).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].id, "1");
+        assert_eq!(rules[0].kind, RuleKind::WholeWord);
+        assert!(rules[0].enabled);
+
+        let bare = r#"[{ "pattern": "a", "replacement": "b", "kind": "regex" }, { "pattern": "c", "replacement": "d" }]"#;
+        let rules = parse_rules(bare).unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].kind, RuleKind::Regex);
+        assert!(rules[0].id.is_empty());
+
+        assert!(parse_rules("{ \"nope\": true }").is_err());
+    }
+}
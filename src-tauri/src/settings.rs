@@ -1,14 +1,117 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use dirs::config_dir;
 use tauri::command;
+use crate::actions::HotkeyBinding;
+use crate::focus::FocusChangePolicy;
+use crate::formatting::FormattingCommand;
+use crate::hotkey::{self, Hotkey, Key};
+use crate::keystate::RecordingMode;
+use crate::normalization::NumberStyle;
+use crate::output::OutputSink;
+use crate::textinjection::InjectionMethod;
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct Settings {
     pub groq_api_key: Option<String>,
     pub save_history: bool,
     pub save_audio: bool,
+    #[serde(default)]
+    pub transcription_provider: Option<String>,
+    #[serde(default)]
+    pub local_endpoint: Option<String>,
+    #[serde(default)]
+    pub local_model: Option<String>,
+    #[serde(default)]
+    pub streaming_enabled: bool,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub translate_mode: bool,
+    #[serde(default)]
+    pub llm_cleanup_enabled: bool,
+    #[serde(default)]
+    pub llm_endpoint: Option<String>,
+    #[serde(default)]
+    pub llm_api_key: Option<String>,
+    #[serde(default)]
+    pub llm_model: Option<String>,
+    #[serde(default)]
+    pub llm_system_prompt: Option<String>,
+    #[serde(default)]
+    pub llm_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub disfluency_filter_enabled: bool,
+    #[serde(default)]
+    pub number_normalization_enabled: bool,
+    #[serde(default)]
+    pub number_style: NumberStyle,
+    #[serde(default)]
+    pub formatting_commands_enabled: bool,
+    #[serde(default)]
+    pub code_mode: bool,
+    /// User-edited spoken command tables keyed by language code; languages
+    /// without an entry use the built-in defaults.
+    #[serde(default)]
+    pub formatting_commands: HashMap<String, Vec<FormattingCommand>>,
+    /// Drops the final period of a single-sentence dictation, for chat apps.
+    #[serde(default)]
+    pub strip_trailing_period: bool,
+    #[serde(default)]
+    pub injection_method: InjectionMethod,
+    /// Pause after each typed character; raise it for apps that drop keys.
+    #[serde(default)]
+    pub injection_key_delay_ms: Option<u64>,
+    /// Pause between the keys of the paste shortcut.
+    #[serde(default)]
+    pub paste_chord_delay_ms: Option<u64>,
+    /// In hybrid mode, texts with at least this many characters are pasted.
+    #[serde(default)]
+    pub hybrid_paste_threshold: Option<usize>,
+    /// How long pasted text stays on the clipboard before the previous
+    /// contents are put back.
+    #[serde(default)]
+    pub clipboard_restore_delay_ms: Option<u64>,
+    /// Push-to-talk hotkey such as "RCtrl" or "Ctrl+Alt+Space"; `None` uses
+    /// the platform default.
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    /// Further hotkeys, each bound to its own action.
+    #[serde(default)]
+    pub hotkey_bindings: Vec<HotkeyBinding>,
+    /// Shorter hotkey presses are taps: discarded in hold mode, hands-free
+    /// recording in toggle mode.
+    #[serde(default)]
+    pub min_hold_ms: Option<u64>,
+    #[serde(default)]
+    pub recording_mode: RecordingMode,
+    /// Key that aborts a recording without transcribing it; `None` means
+    /// Escape and an empty string disables it.
+    #[serde(default)]
+    pub cancel_key: Option<String>,
+    /// Keep a history entry for recordings aborted with the cancel key.
+    #[serde(default)]
+    pub save_cancelled_to_history: bool,
+    #[serde(default)]
+    pub focus_change_policy: FocusChangePolicy,
+    /// Types the text held by [`FocusChangePolicy::Pending`]; `None` or
+    /// empty disables it.
+    #[serde(default)]
+    pub pending_paste_hotkey: Option<String>,
+    /// Takes back the last dictation; `None` or empty disables it.
+    #[serde(default)]
+    pub undo_hotkey: Option<String>,
+    /// Where dictations go; empty means typing them into the focused window.
+    #[serde(default)]
+    pub outputs: Vec<OutputSink>,
+    /// File the Markdown output appends to; `None` uses dictations.md in the
+    /// config directory.
+    #[serde(default)]
+    pub markdown_notes_path: Option<String>,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
 }
 
 fn settings_path() -> PathBuf {
@@ -34,6 +137,13 @@ fn save_settings_to_file(settings: &Settings) -> Result<(), String> {
     fs::write(path, data).map_err(|e| e.to_string())
 }
 
+/// Loads the settings, lets `update` modify them and writes them back.
+pub fn update_settings(update: impl FnOnce(&mut Settings)) -> Result<(), String> {
+    let mut settings = load_settings();
+    update(&mut settings);
+    save_settings_to_file(&settings)
+}
+
 #[command]
 pub fn get_settings() -> Result<Settings, String> {
     Ok(load_settings())
@@ -52,6 +162,228 @@ pub fn save_settings(groq_api_key: String, save_history: Option<bool>, save_audi
     save_settings_to_file(&settings)
 }
 
+#[command]
+pub fn set_translate_mode(enabled: bool) -> Result<(), String> {
+    update_settings(|settings| settings.translate_mode = enabled)
+}
+
+#[command]
+pub fn set_code_mode(enabled: bool) -> Result<(), String> {
+    update_settings(|settings| settings.code_mode = enabled)
+}
+
+/// Validates and stores the push-to-talk hotkey and switches the key monitor
+/// over to it. Returns the hotkey in canonical form, e.g. "Ctrl+Alt+Space".
+#[command]
+pub fn set_hotkey(hotkey: String) -> Result<String, String> {
+    let parsed = Hotkey::parse(&hotkey)?;
+    let mut hotkeys = vec![parsed.clone()];
+    hotkeys.extend(load_settings().hotkey_bindings.iter().filter_map(|b| Hotkey::parse(&b.hotkey).ok()));
+    hotkey::check_overlaps(&hotkeys)?;
+    let canonical = parsed.to_string();
+    update_settings(|settings| settings.hotkey = Some(canonical))?;
+    hotkey::reload();
+    Ok(parsed.to_string())
+}
+
+/// Replaces the extra hotkeys. Each must be valid and must not overlap the
+/// main hotkey or each other. Returns them with the hotkeys in canonical form.
+#[command]
+pub fn set_hotkey_bindings(bindings: Vec<HotkeyBinding>) -> Result<Vec<HotkeyBinding>, String> {
+    let mut hotkeys = vec![Hotkey::from_settings(&load_settings())];
+    let mut canonical = Vec::new();
+    for binding in bindings {
+        let parsed = Hotkey::parse(&binding.hotkey)?;
+        canonical.push(HotkeyBinding { hotkey: parsed.to_string(), ..binding });
+        hotkeys.push(parsed);
+    }
+    hotkey::check_overlaps(&hotkeys)?;
+    update_settings(|settings| settings.hotkey_bindings = canonical.clone())?;
+    hotkey::reload();
+    Ok(canonical)
+}
+
+/// Parses a hotkey that isn't for dictation and checks it against the
+/// dictation hotkeys and `others`. Returns it in canonical form, or an empty
+/// string for an empty input, which disables it.
+fn canonical_extra_hotkey(hotkey: &str, others: &[Option<&String>]) -> Result<String, String> {
+    if hotkey.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let parsed = Hotkey::parse(hotkey)?;
+    let settings = load_settings();
+    let mut hotkeys = vec![parsed.clone(), Hotkey::from_settings(&settings)];
+    hotkeys.extend(settings.hotkey_bindings.iter().filter_map(|b| Hotkey::parse(&b.hotkey).ok()));
+    hotkeys.extend(others.iter().flatten().filter_map(|h| Hotkey::parse(h).ok()));
+    hotkey::check_overlaps(&hotkeys)?;
+    Ok(parsed.to_string())
+}
+
+/// Sets the hotkey that types held-back text; an empty string disables it.
+/// Returns the hotkey in canonical form.
+#[command]
+pub fn set_pending_paste_hotkey(hotkey: String) -> Result<String, String> {
+    let canonical = canonical_extra_hotkey(&hotkey, &[load_settings().undo_hotkey.as_ref()])?;
+    update_settings(|settings| settings.pending_paste_hotkey = Some(canonical.clone()))?;
+    hotkey::reload();
+    Ok(canonical)
+}
+
+/// Sets the hotkey that undoes the last dictation; an empty string
+/// disables it. Returns the hotkey in canonical form.
+#[command]
+pub fn set_undo_hotkey(hotkey: String) -> Result<String, String> {
+    let canonical = canonical_extra_hotkey(&hotkey, &[load_settings().pending_paste_hotkey.as_ref()])?;
+    update_settings(|settings| settings.undo_hotkey = Some(canonical.clone()))?;
+    hotkey::reload();
+    Ok(canonical)
+}
+
+/// Sets the key that aborts a recording; an empty string disables it.
+/// Returns the key in canonical form.
+#[command]
+pub fn set_cancel_key(key: String) -> Result<String, String> {
+    let canonical = if key.trim().is_empty() { String::new() } else { Key::parse(&key)?.to_string() };
+    update_settings(|settings| settings.cancel_key = Some(canonical.clone()))?;
+    hotkey::reload();
+    Ok(canonical)
+}
+
+#[command]
+pub fn set_recording_mode(mode: RecordingMode) -> Result<(), String> {
+    update_settings(|settings| settings.recording_mode = mode)?;
+    hotkey::reload();
+    Ok(())
+}
+
+/// Sets how long the hotkey must be held to count; `None` restores the default.
+#[command]
+pub fn set_min_hold_ms(min_hold_ms: Option<u64>) -> Result<(), String> {
+    update_settings(|settings| settings.min_hold_ms = min_hold_ms)?;
+    hotkey::reload();
+    Ok(())
+}
+
+#[command]
+pub fn save_llm_settings(
+    enabled: bool,
+    endpoint: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    system_prompt: Option<String>,
+    timeout_ms: Option<u64>,
+) -> Result<(), String> {
+    update_settings(|settings| {
+        settings.llm_cleanup_enabled = enabled;
+        settings.llm_endpoint = endpoint;
+        settings.llm_api_key = api_key;
+        settings.llm_model = model;
+        settings.llm_system_prompt = system_prompt;
+        settings.llm_timeout_ms = timeout_ms;
+    })
+}
+
+/// Stores the transcription backend and whether to stream while recording.
+/// `None` uses the Groq API and the default local model.
+#[command]
+pub fn save_transcription_settings(
+    provider: Option<String>,
+    local_endpoint: Option<String>,
+    local_model: Option<String>,
+    streaming_enabled: bool,
+) -> Result<(), String> {
+    update_settings(|settings| {
+        settings.transcription_provider = provider;
+        settings.local_endpoint = local_endpoint;
+        settings.local_model = local_model;
+        settings.streaming_enabled = streaming_enabled;
+    })
+}
+
+/// Sets the spoken language as an ISO-639-1 code; an empty string lets
+/// Whisper detect it.
+#[command]
+pub fn set_language(language: String) -> Result<(), String> {
+    update_settings(|settings| settings.language = Some(language).filter(|l| !l.trim().is_empty()))
+}
+
+#[command]
+pub fn set_formatting_commands_enabled(enabled: bool) -> Result<(), String> {
+    update_settings(|settings| settings.formatting_commands_enabled = enabled)
+}
+
+#[command]
+pub fn set_disfluency_filter_enabled(enabled: bool) -> Result<(), String> {
+    update_settings(|settings| settings.disfluency_filter_enabled = enabled)
+}
+
+#[command]
+pub fn save_number_settings(enabled: bool, style: NumberStyle) -> Result<(), String> {
+    update_settings(|settings| {
+        settings.number_normalization_enabled = enabled;
+        settings.number_style = style;
+    })
+}
+
+#[command]
+pub fn set_strip_trailing_period(enabled: bool) -> Result<(), String> {
+    update_settings(|settings| settings.strip_trailing_period = enabled)
+}
+
+#[command]
+pub fn set_injection_method(method: InjectionMethod) -> Result<(), String> {
+    update_settings(|settings| settings.injection_method = method)
+}
+
+#[command]
+pub fn set_save_cancelled_to_history(enabled: bool) -> Result<(), String> {
+    update_settings(|settings| settings.save_cancelled_to_history = enabled)
+}
+
+#[command]
+pub fn set_focus_change_policy(policy: FocusChangePolicy) -> Result<(), String> {
+    update_settings(|settings| settings.focus_change_policy = policy)
+}
+
+/// Stores the injection timing; `None` uses the built-in default.
+#[command]
+pub fn save_injection_timing(
+    key_delay_ms: Option<u64>,
+    paste_chord_delay_ms: Option<u64>,
+    hybrid_paste_threshold: Option<usize>,
+    clipboard_restore_delay_ms: Option<u64>,
+) -> Result<(), String> {
+    update_settings(|settings| {
+        settings.injection_key_delay_ms = key_delay_ms;
+        settings.paste_chord_delay_ms = paste_chord_delay_ms;
+        settings.hybrid_paste_threshold = hybrid_paste_threshold;
+        settings.clipboard_restore_delay_ms = clipboard_restore_delay_ms;
+    })
+}
+
+/// Stores where dictations go. The webhook output needs an http(s) URL.
+#[command]
+pub fn save_output_settings(
+    outputs: Vec<OutputSink>,
+    markdown_notes_path: Option<String>,
+    webhook_url: Option<String>,
+) -> Result<(), String> {
+    let webhook_url = webhook_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    if let Some(url) = &webhook_url {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("Invalid webhook URL '{}': it must start with http:// or https://", url));
+        }
+    }
+    if outputs.contains(&OutputSink::Webhook) && webhook_url.is_none() {
+        return Err("The webhook output needs a URL".to_string());
+    }
+    update_settings(|settings| {
+        settings.outputs = outputs;
+        settings.markdown_notes_path = markdown_notes_path.filter(|path| !path.trim().is_empty());
+        settings.webhook_url = webhook_url;
+    })
+}
+
 #[command]
 pub fn reset_settings() -> Result<(), String> {
     let mut settings = load_settings();
@@ -0,0 +1,272 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter};
+use crate::audio::{self, TARGET_SAMPLE_RATE};
+use crate::transcription::{self, Provider, Task, TranscriptionResult};
+
+// VAD frames are 30 ms at 16 kHz.
+const FRAME_SAMPLES: usize = 480;
+const SILENCE_RMS: f32 = 0.01;
+// A chunk is only cut once it holds this much audio...
+const MIN_CHUNK_SAMPLES: usize = TARGET_SAMPLE_RATE as usize * 3;
+// ...and the speaker has paused for at least this long.
+const MIN_SILENCE_FRAMES: usize = 16;
+// Long run-on speech without pauses is cut here regardless.
+const MAX_CHUNK_SAMPLES: usize = TARGET_SAMPLE_RATE as usize * 25;
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+struct StreamingSession {
+    stop: Arc<AtomicBool>,
+    cancel: Arc<AtomicBool>,
+    worker: JoinHandle<Option<StreamedText>>,
+}
+
+#[derive(Default)]
+struct StreamedText {
+    parts: Vec<String>,
+    language: Option<String>,
+}
+
+static SESSION: Lazy<Mutex<Option<StreamingSession>>> = Lazy::new(|| Mutex::new(None));
+
+/// Starts cutting the current recording into VAD-delimited chunks and
+/// transcribing them in the background, emitting `transcription-partial`
+/// with the text so far after every chunk.
+pub fn start(app: AppHandle, provider: Provider, task: Task) {
+    audio::enable_streaming();
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (stop_worker, cancel_worker) = (stop.clone(), cancel.clone());
+    let worker = thread::spawn(move || run_worker(&app, &provider, task, &stop_worker, &cancel_worker));
+
+    let previous = SESSION.lock().unwrap().replace(StreamingSession { stop, cancel, worker });
+    if let Some(previous) = previous {
+        previous.cancel.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Abandons the running session without waiting for it: the worker stops
+/// before its next chunk and nothing more is sent to the API.
+pub fn cancel() {
+    if let Some(session) = SESSION.lock().unwrap().take() {
+        session.cancel.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Stops the worker, transcribes whatever audio is left and returns the
+/// combined result. Returns `None` when no session is running or a chunk
+/// failed, in which case the caller should fall back to the full recording.
+pub fn finish() -> Option<TranscriptionResult> {
+    let session = SESSION.lock().unwrap().take()?;
+    let start = Instant::now();
+    session.stop.store(true, Ordering::SeqCst);
+    let streamed = session.worker.join().ok()??;
+    Some(TranscriptionResult {
+        text: streamed.parts.join(" "),
+        round_trip_ms: start.elapsed().as_millis() as u64,
+        status: "success".to_string(),
+        error: None,
+        language: streamed.language,
+    })
+}
+
+fn run_worker(app: &AppHandle, provider: &Provider, task: Task, stop: &AtomicBool, cancel: &AtomicBool) -> Option<StreamedText> {
+    let mut pending: Vec<f32> = Vec::new();
+    let mut streamed = StreamedText::default();
+    let mut chunk_index = 0;
+
+    loop {
+        let stopping = stop.load(Ordering::SeqCst);
+        pending.extend(audio::drain_stream_samples());
+
+        while let Some(split) = find_split(&pending) {
+            if cancel.load(Ordering::SeqCst) {
+                return None;
+            }
+            let rest = pending.split_off(split);
+            let chunk = std::mem::replace(&mut pending, rest);
+            transcribe_chunk(provider, task, &chunk, chunk_index, &mut streamed)?;
+            chunk_index += 1;
+            let _ = app.emit_to("main", "transcription-partial", streamed.parts.join(" "));
+        }
+
+        if cancel.load(Ordering::SeqCst) {
+            return None;
+        }
+        if stopping {
+            transcribe_chunk(provider, task, &pending, chunk_index, &mut streamed)?;
+            return Some(streamed);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn transcribe_chunk(provider: &Provider, task: Task, samples: &[f32], index: usize, streamed: &mut StreamedText) -> Option<()> {
+    // Whisper tends to hallucinate text for pure silence, so skip those chunks.
+    if !contains_speech(samples) {
+        return Some(());
+    }
+    let path = std::env::temp_dir().join(format!("vwisper_stream_{}.wav", index));
+    if let Err(e) = audio::write_wav(&path, samples) {
+        eprintln!("Failed to write streaming chunk: {}", e);
+        return None;
+    }
+    let result = transcription::transcribe_audio(path.to_str().unwrap(), provider, task);
+    let _ = std::fs::remove_file(&path);
+    if result.status != "success" {
+        eprintln!("Streaming chunk failed: {}", result.error.unwrap_or_default());
+        return None;
+    }
+    let text = result.text.trim();
+    if !text.is_empty() {
+        streamed.parts.push(text.to_string());
+    }
+    if streamed.language.is_none() {
+        streamed.language = result.language;
+    }
+    Some(())
+}
+
+fn frame_rms(frame: &[f32]) -> f32 {
+    (frame.iter().map(|&x| x * x).sum::<f32>() / frame.len() as f32).sqrt()
+}
+
+fn contains_speech(samples: &[f32]) -> bool {
+    samples.chunks(FRAME_SAMPLES).any(|frame| frame_rms(frame) >= SILENCE_RMS)
+}
+
+/// Returns the sample index to cut at: the middle of the first long enough
+/// pause after `MIN_CHUNK_SAMPLES`, or `MAX_CHUNK_SAMPLES` if nobody paused.
+fn find_split(samples: &[f32]) -> Option<usize> {
+    if samples.len() < MIN_CHUNK_SAMPLES {
+        return None;
+    }
+    let mut silent_run = 0;
+    for (i, frame) in samples.chunks_exact(FRAME_SAMPLES).enumerate() {
+        if frame_rms(frame) < SILENCE_RMS {
+            silent_run += 1;
+        } else {
+            silent_run = 0;
+        }
+        let end = (i + 1) * FRAME_SAMPLES;
+        if end >= MIN_CHUNK_SAMPLES && silent_run >= MIN_SILENCE_FRAMES {
+            return Some(end - silent_run * FRAME_SAMPLES / 2);
+        }
+    }
+    if samples.len() >= MAX_CHUNK_SAMPLES {
+        Some(MAX_CHUNK_SAMPLES)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAMES_PER_SECOND: usize = TARGET_SAMPLE_RATE as usize / FRAME_SAMPLES;
+
+    fn speech(frames: usize) -> Vec<f32> {
+        (0..frames * FRAME_SAMPLES).map(|i| if i % 2 == 0 { 0.2 } else { -0.2 }).collect()
+    }
+
+    fn silence(frames: usize) -> Vec<f32> {
+        vec![0.0; frames * FRAME_SAMPLES]
+    }
+
+    fn concat(parts: &[Vec<f32>]) -> Vec<f32> {
+        parts.concat()
+    }
+
+    #[test]
+    fn silence_and_room_noise_are_not_speech() {
+        assert!(!contains_speech(&silence(10)));
+        assert!(!contains_speech(&vec![0.001; 10 * FRAME_SAMPLES]));
+        assert!(!contains_speech(&[]));
+    }
+
+    #[test]
+    fn a_single_loud_frame_is_speech() {
+        assert!(contains_speech(&concat(&[silence(10), speech(1), silence(10)])));
+    }
+
+    #[test]
+    fn no_split_before_the_minimum_chunk_length() {
+        let samples = concat(&[speech(FRAMES_PER_SECOND), silence(MIN_SILENCE_FRAMES * 2)]);
+        assert!(samples.len() < MIN_CHUNK_SAMPLES);
+        assert_eq!(find_split(&samples), None);
+    }
+
+    #[test]
+    fn splits_in_the_middle_of_the_first_pause_after_the_minimum() {
+        let speech_frames = MIN_CHUNK_SAMPLES / FRAME_SAMPLES;
+        let samples = concat(&[speech(speech_frames), silence(MIN_SILENCE_FRAMES), speech(10)]);
+        let expected = (speech_frames + MIN_SILENCE_FRAMES / 2) * FRAME_SAMPLES;
+        assert_eq!(find_split(&samples), Some(expected));
+    }
+
+    #[test]
+    fn short_pauses_do_not_split() {
+        let samples = concat(&[
+            speech(MIN_CHUNK_SAMPLES / FRAME_SAMPLES),
+            silence(MIN_SILENCE_FRAMES - 1),
+            speech(FRAMES_PER_SECOND),
+        ]);
+        assert_eq!(find_split(&samples), None);
+    }
+
+    #[test]
+    fn a_pause_before_the_minimum_is_skipped_for_a_later_one() {
+        let samples = concat(&[
+            speech(FRAMES_PER_SECOND),
+            silence(MIN_SILENCE_FRAMES * 2),
+            speech(3 * FRAMES_PER_SECOND),
+            silence(MIN_SILENCE_FRAMES),
+        ]);
+        let speech_end = (FRAMES_PER_SECOND + MIN_SILENCE_FRAMES * 2 + 3 * FRAMES_PER_SECOND) * FRAME_SAMPLES;
+        assert_eq!(find_split(&samples), Some(speech_end + MIN_SILENCE_FRAMES / 2 * FRAME_SAMPLES));
+    }
+
+    #[test]
+    fn run_on_speech_is_cut_at_the_maximum() {
+        let max_frames = MAX_CHUNK_SAMPLES / FRAME_SAMPLES;
+        assert_eq!(find_split(&speech(max_frames - 1)), None);
+        assert_eq!(find_split(&speech(max_frames + FRAMES_PER_SECOND)), Some(MAX_CHUNK_SAMPLES));
+    }
+
+    #[test]
+    fn streamed_audio_is_cut_into_bounded_chunks_without_losing_samples() {
+        let audio = concat(&[
+            speech(4 * FRAMES_PER_SECOND),
+            silence(MIN_SILENCE_FRAMES),
+            speech(30 * FRAMES_PER_SECOND),
+            // Longer than the minimum: after the 25 s cut the VAD frames no
+            // longer line up with this synthetic audio.
+            silence(MIN_SILENCE_FRAMES * 2),
+            speech(FRAMES_PER_SECOND),
+        ]);
+        // The worker's loop: audio arrives every poll interval and chunks
+        // are cut off the front until no split is left.
+        let poll_samples = TARGET_SAMPLE_RATE as usize * POLL_INTERVAL.as_millis() as usize / 1000;
+        let mut pending = Vec::new();
+        let mut chunks = Vec::new();
+        for block in audio.chunks(poll_samples) {
+            pending.extend_from_slice(block);
+            while let Some(split) = find_split(&pending) {
+                let rest = pending.split_off(split);
+                chunks.push(std::mem::replace(&mut pending, rest));
+            }
+        }
+        chunks.push(pending);
+
+        // The first pause, the 25 s limit, the second pause, and the rest.
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks[1].len(), MAX_CHUNK_SAMPLES);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= MAX_CHUNK_SAMPLES));
+        assert_eq!(chunks.concat(), audio);
+    }
+}
@@ -0,0 +1,52 @@
+/// A whitespace-separated word split into its leading punctuation, the word
+/// itself and trailing punctuation, e.g. `"Hello,` -> (`"`, `Hello`, `,`).
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub lead: String,
+    pub word: String,
+    pub trail: String,
+}
+
+impl Token {
+    pub fn parse(raw: &str) -> Self {
+        let word_start = raw.find(|c: char| c.is_alphanumeric()).unwrap_or(raw.len());
+        let word_end = raw
+            .rfind(|c: char| c.is_alphanumeric())
+            .map(|i| i + raw[i..].chars().next().map_or(1, |c| c.len_utf8()))
+            .unwrap_or(word_start)
+            .max(word_start);
+        Self {
+            lead: raw[..word_start].to_string(),
+            word: raw[word_start..word_end].to_string(),
+            trail: raw[word_end..].to_string(),
+        }
+    }
+
+    pub fn key(&self) -> String {
+        self.word.to_lowercase()
+    }
+
+    pub fn ends_sentence(&self) -> bool {
+        self.trail.contains(['.', '?', '!'])
+    }
+
+    /// A word cut off mid-way, e.g. "sto-" in "the sto- the store".
+    pub fn is_cut_off(&self) -> bool {
+        !self.word.is_empty() && (self.trail == "-" || self.trail == "—" || self.trail == "–")
+    }
+
+    pub fn starts_uppercase(&self) -> bool {
+        self.word.chars().next().is_some_and(|c| c.is_uppercase())
+    }
+
+    pub fn capitalize(&mut self) {
+        let mut chars = self.word.chars();
+        if let Some(first) = chars.next() {
+            self.word = first.to_uppercase().chain(chars).collect();
+        }
+    }
+
+    pub fn render(&self) -> String {
+        format!("{}{}{}", self.lead, self.word, self.trail)
+    }
+}
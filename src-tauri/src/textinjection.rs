@@ -1,14 +1,32 @@
 use enigo::{Enigo, Keyboard, Settings};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use once_cell::sync::Lazy;
-use log::info;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
 use std::thread;
 use std::time::Duration;
+use arboard::{Clipboard, ImageData};
 
-#[cfg(any(target_os = "windows", target_os = "macos"))]
-use clipboard::ClipboardProvider;
+#[cfg(target_os = "linux")]
+use crate::platform::linux::LinuxSession;
+
+/// How text reaches the focused application.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InjectionMethod {
+    /// The platform default: paste on Windows, typing elsewhere.
+    #[default]
+    Auto,
+    /// Put the text on the clipboard and send Ctrl+V (Cmd+V on macOS).
+    Paste,
+    /// Type the text character by character.
+    Type,
+    /// Paste long text and type short text.
+    Hybrid,
+}
 
-#[cfg(any(target_os = "windows", target_os = "macos"))]
 static TEXT_INJECTOR: Lazy<Arc<Mutex<Option<Enigo>>>> = Lazy::new(|| {
     Arc::new(Mutex::new(None))
 });
@@ -29,20 +47,35 @@ pub fn init_text_injector() -> Result<(), Box<dyn std::error::Error>> {
     
     #[cfg(target_os = "linux")]
     {
-        let settings = Settings::default();
-        
-        let enigo = Enigo::new(&settings).map_err(|e| format!("Failed to create Enigo instance: {}", e))?;
-        
-        let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
-        *injector_guard = Some(enigo);
-        
-        info!("Text injector initialized for {}", std::env::consts::OS);
+        let session = LinuxSession::detect();
+        match Enigo::new(&Settings::default()) {
+            Ok(enigo) => {
+                let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
+                *injector_guard = Some(enigo);
+            }
+            // Wayland sessions without XWayland have no X display; the
+            // wtype/ydotool helpers below work without one.
+            Err(e) if session == LinuxSession::Wayland => {
+                warn!("No X11 keyboard injection available ({}), using Wayland helpers only", e);
+            }
+            Err(e) => return Err(format!("Failed to create Enigo instance: {}", e).into()),
+        }
+
+        if session == LinuxSession::Wayland && !has_command("wtype") && !has_command("ydotool") {
+            warn!("Neither wtype nor ydotool is installed; dictated text will only be placed on the clipboard");
+        }
+        info!("Text injector initialized for {} ({:?} session)", std::env::consts::OS, session);
     }
     
     Ok(())
 }
 
 pub fn is_text_injector_initialized() -> bool {
+    #[cfg(target_os = "linux")]
+    if LinuxSession::detect() == LinuxSession::Wayland {
+        return true;
+    }
+
     let injector_guard = TEXT_INJECTOR.lock().unwrap();
     injector_guard.is_some()
 }
@@ -54,6 +87,18 @@ pub fn test_text_injection() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 pub fn inject_text(text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    inject_text_with(text, &InjectionConfig::default()).map(|_| ())
+}
+
+/// How the text ended up in the application, which decides how it can be
+/// taken back: typed text is erased, a paste is undone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Injected {
+    Typed,
+    Pasted,
+}
+
+pub fn inject_text_with(text: &str, config: &InjectionConfig) -> Result<Injected, Box<dyn std::error::Error>> {
     if !is_text_injector_initialized() {
         return Err("Text injector not initialized. Please restart the application.".into());
     }
@@ -65,167 +110,606 @@ pub fn inject_text(text: &str) -> Result<(), Box<dyn std::error::Error>> {
     // Add a small delay to ensure the target window is fully focused
     thread::sleep(Duration::from_millis(200));
 
-    #[cfg(target_os = "windows")]
-    {
-        info!("Injecting text on Windows: '{}'", text);
-        return inject_text_via_clipboard(text);
+    let strategy = config.strategy();
+    info!("Injecting text on {} via {}: '{}'", std::env::consts::OS, strategy.name(), text);
+    strategy.inject(text)
+}
+
+/// A way of getting text into the focused application. Some apps ignore
+/// synthetic paste, others drop characters when typed to, so the user picks
+/// one globally and per application.
+pub trait InjectionStrategy {
+    fn name(&self) -> &'static str;
+    fn inject(&self, text: &str) -> Result<Injected, Box<dyn std::error::Error>>;
+}
+
+/// Puts the text on the clipboard and sends Ctrl+V (Cmd+V on macOS).
+pub struct PasteStrategy {
+    /// Pause between the keys of the paste shortcut.
+    pub chord_delay: Duration,
+    /// Typing used when the paste shortcut can't be sent.
+    pub fallback_key_delay: Duration,
+    /// How long the pasted text stays on the clipboard before the user's
+    /// clipboard is put back.
+    pub restore_delay: Duration,
+}
+
+/// Types the text character by character.
+pub struct TypeStrategy {
+    pub key_delay: Duration,
+}
+
+/// Pastes long text, which would take a while to type, and types short
+/// text, which leaves the clipboard alone.
+pub struct HybridStrategy {
+    /// Texts with at least this many characters are pasted.
+    pub threshold: usize,
+    pub paste: PasteStrategy,
+    pub typing: TypeStrategy,
+}
+
+impl InjectionStrategy for PasteStrategy {
+    fn name(&self) -> &'static str {
+        "paste"
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        info!("Injecting text on macOS via direct typing (clipboard-free): '{}'", text);
-        return inject_text_char_by_character(text);
+    fn inject(&self, text: &str) -> Result<Injected, Box<dyn std::error::Error>> {
+        #[cfg(target_os = "linux")]
+        if LinuxSession::detect() == LinuxSession::Wayland {
+            return inject_text_wayland_clipboard(text, self.restore_delay).map(|_| Injected::Pasted);
+        }
+        inject_text_via_clipboard(text, self)
     }
-    
+}
+
+impl InjectionStrategy for TypeStrategy {
+    fn name(&self) -> &'static str {
+        "type"
+    }
+
+    fn inject(&self, text: &str) -> Result<Injected, Box<dyn std::error::Error>> {
+        #[cfg(target_os = "linux")]
+        if LinuxSession::detect() == LinuxSession::Wayland {
+            return inject_text_wayland(text, self.key_delay);
+        }
+        inject_text_char_by_character(text, self.key_delay).map(|_| Injected::Typed)
+    }
+}
+
+impl InjectionStrategy for HybridStrategy {
+    fn name(&self) -> &'static str {
+        "hybrid"
+    }
+
+    fn inject(&self, text: &str) -> Result<Injected, Box<dyn std::error::Error>> {
+        if text.chars().count() >= self.threshold {
+            self.paste.inject(text)
+        } else {
+            self.typing.inject(text)
+        }
+    }
+}
+
+pub const DEFAULT_KEY_DELAY_MS: u64 = 6;
+pub const DEFAULT_PASTE_CHORD_DELAY_MS: u64 = 50;
+pub const DEFAULT_HYBRID_THRESHOLD: usize = 80;
+pub const DEFAULT_CLIPBOARD_RESTORE_DELAY_MS: u64 = 200;
+
+/// The injection settings in effect for one dictation.
+#[derive(Debug, Clone, Copy)]
+pub struct InjectionConfig {
+    pub method: InjectionMethod,
+    pub key_delay: Duration,
+    pub chord_delay: Duration,
+    pub hybrid_threshold: usize,
+    pub clipboard_restore_delay: Duration,
+}
+
+impl Default for InjectionConfig {
+    fn default() -> Self {
+        Self {
+            method: InjectionMethod::Auto,
+            key_delay: Duration::from_millis(DEFAULT_KEY_DELAY_MS),
+            chord_delay: Duration::from_millis(DEFAULT_PASTE_CHORD_DELAY_MS),
+            hybrid_threshold: DEFAULT_HYBRID_THRESHOLD,
+            clipboard_restore_delay: Duration::from_millis(DEFAULT_CLIPBOARD_RESTORE_DELAY_MS),
+        }
+    }
+}
+
+impl InjectionConfig {
+    pub fn from_settings(settings: &crate::settings::Settings) -> Self {
+        Self {
+            method: settings.injection_method,
+            key_delay: Duration::from_millis(settings.injection_key_delay_ms.unwrap_or(DEFAULT_KEY_DELAY_MS)),
+            chord_delay: Duration::from_millis(settings.paste_chord_delay_ms.unwrap_or(DEFAULT_PASTE_CHORD_DELAY_MS)),
+            hybrid_threshold: settings.hybrid_paste_threshold.unwrap_or(DEFAULT_HYBRID_THRESHOLD),
+            clipboard_restore_delay: Duration::from_millis(
+                settings.clipboard_restore_delay_ms.unwrap_or(DEFAULT_CLIPBOARD_RESTORE_DELAY_MS),
+            ),
+        }
+    }
+
+    pub fn strategy(&self) -> Box<dyn InjectionStrategy> {
+        let paste = PasteStrategy {
+            chord_delay: self.chord_delay,
+            fallback_key_delay: self.key_delay,
+            restore_delay: self.clipboard_restore_delay,
+        };
+        let typing = TypeStrategy { key_delay: self.key_delay };
+        match self.method {
+            // macOS types by default to stay clear of NSPasteboard off the main thread.
+            InjectionMethod::Auto if cfg!(target_os = "windows") => Box::new(paste),
+            InjectionMethod::Auto | InjectionMethod::Type => Box::new(typing),
+            InjectionMethod::Paste => Box::new(paste),
+            InjectionMethod::Hybrid => Box::new(HybridStrategy { threshold: self.hybrid_threshold, paste, typing }),
+        }
+    }
+}
+
+/// Leaves `text` on the clipboard for the user to paste themselves. A
+/// pending restore from an earlier paste would overwrite it, so it is
+/// cancelled.
+pub fn copy_to_clipboard(text: &str) -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(target_os = "linux")]
-    {
-        info!("Injecting text on {}: '{}'", std::env::consts::OS, text);
-        
-        let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
-        
-        if let Some(injector) = injector_guard.as_mut() {
-            thread::sleep(Duration::from_millis(50));
-            
-            match injector.text(text) {
-                Ok(_) => {
-                    info!("Direct text injection successful");
-                    thread::sleep(Duration::from_millis(50));
-                    Ok(())
+    if LinuxSession::detect() == LinuxSession::Wayland {
+        wl_copy(text.as_bytes(), None)?;
+        forget_saved_clipboard();
+        return Ok(());
+    }
+
+    with_clipboard(|clipboard| clipboard.set_text(text))?;
+    forget_saved_clipboard();
+    Ok(())
+}
+
+/// One clipboard for the whole app. On X11 the owner of the selection has
+/// to stay alive to serve it, so it is never dropped.
+static CLIPBOARD: Lazy<Mutex<Option<Clipboard>>> = Lazy::new(|| Mutex::new(None));
+
+fn with_clipboard<T>(f: impl FnOnce(&mut Clipboard) -> Result<T, arboard::Error>) -> Result<T, String> {
+    let mut guard = CLIPBOARD.lock().unwrap();
+    if guard.is_none() {
+        *guard = Some(Clipboard::new().map_err(|e| format!("Failed to initialize clipboard: {}", e))?);
+    }
+    f(guard.as_mut().unwrap()).map_err(|e| format!("Clipboard error: {}", e))
+}
+
+/// What the clipboard held before a paste. The clipboard can offer several
+/// formats at once but arboard writes one at a time, so the richest one is
+/// kept: copied files, then rich text with its plain-text version, then an
+/// image, then plain text.
+enum ClipboardSnapshot {
+    Files(Vec<PathBuf>),
+    Html { html: String, text: Option<String> },
+    Image(ImageData<'static>),
+    Text(String),
+    /// Wayland, read through wl-paste: the richest MIME type on offer.
+    #[cfg(target_os = "linux")]
+    Wayland { mime: String, data: Vec<u8> },
+}
+
+impl ClipboardSnapshot {
+    fn take() -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        if LinuxSession::detect() == LinuxSession::Wayland {
+            return wl_snapshot();
+        }
+
+        with_clipboard(|clipboard| {
+            if let Ok(files) = clipboard.get().file_list() {
+                if !files.is_empty() {
+                    return Ok(Some(ClipboardSnapshot::Files(files)));
                 }
-                Err(e) => {
-                    error!("Failed to inject text with Enigo: {}", e);
-                    Err(format!("Failed to inject text: {}", e).into())
+            }
+            let text = clipboard.get_text().ok().filter(|t| !t.is_empty());
+            if let Ok(html) = clipboard.get().html() {
+                if !html.is_empty() {
+                    return Ok(Some(ClipboardSnapshot::Html { html, text }));
                 }
             }
-        } else {
-            error!("Text injector not initialized");
-            Err("Text injector not initialized".into())
+            if let Ok(image) = clipboard.get_image() {
+                return Ok(Some(ClipboardSnapshot::Image(image)));
+            }
+            Ok(text.map(ClipboardSnapshot::Text))
+        })
+        .unwrap_or_else(|e| {
+            warn!("Failed to read the clipboard: {}", e);
+            None
+        })
+    }
+
+    fn restore(self) -> Result<(), String> {
+        match self {
+            ClipboardSnapshot::Files(files) => with_clipboard(|clipboard| clipboard.set().file_list(&files)),
+            ClipboardSnapshot::Html { html, text } => with_clipboard(|clipboard| clipboard.set_html(html, text)),
+            ClipboardSnapshot::Image(image) => with_clipboard(|clipboard| clipboard.set_image(image)),
+            ClipboardSnapshot::Text(text) => with_clipboard(|clipboard| clipboard.set_text(text)),
+            #[cfg(target_os = "linux")]
+            ClipboardSnapshot::Wayland { mime, data } => wl_copy(&data, Some(&mime)),
         }
     }
 }
 
-#[cfg(target_os = "windows")]
-fn inject_text_via_clipboard(text: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// The plain text currently on the clipboard, if any.
+fn clipboard_text() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    if LinuxSession::detect() == LinuxSession::Wayland {
+        return wl_paste(None).and_then(|data| String::from_utf8(data).ok());
+    }
+
+    with_clipboard(|clipboard| clipboard.get_text()).ok()
+}
+
+/// The user's clipboard while dictated text sits on it. A paste that comes
+/// before the previous restore keeps the older, real snapshot.
+static SAVED_CLIPBOARD: Mutex<Option<ClipboardSnapshot>> = Mutex::new(None);
+/// The text the latest paste left on the clipboard, which its restore
+/// expects to still find there.
+static PASTED_TEXT: Mutex<Option<String>> = Mutex::new(None);
+/// Bumped by every paste, so only the latest one restores the clipboard.
+static PASTE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Saves what is on the clipboard unless an earlier paste already did, and
+/// holds off that paste's restore while this one changes the clipboard.
+/// Returns whether a new snapshot was taken.
+fn save_clipboard() -> bool {
+    PASTE_GENERATION.fetch_add(1, Ordering::SeqCst);
+    let mut saved = SAVED_CLIPBOARD.lock().unwrap();
+    if saved.is_some() {
+        return false;
+    }
+    *saved = ClipboardSnapshot::take();
+    true
+}
+
+/// Drops the snapshot and cancels any pending restore, for when the
+/// clipboard should keep what is on it now.
+fn forget_saved_clipboard() {
+    PASTE_GENERATION.fetch_add(1, Ordering::SeqCst);
+    SAVED_CLIPBOARD.lock().unwrap().take();
+    PASTED_TEXT.lock().unwrap().take();
+}
+
+/// For a paste that couldn't put its text on the clipboard. A snapshot it
+/// took itself is dropped, as the clipboard still holds it. One from an
+/// earlier paste is kept and that paste's restore is scheduled again.
+fn clipboard_not_set(new_snapshot: bool, delay: Duration) {
+    if new_snapshot {
+        SAVED_CLIPBOARD.lock().unwrap().take();
+        return;
+    }
+    let pasted = PASTED_TEXT.lock().unwrap().clone();
+    match pasted {
+        Some(pasted) => restore_clipboard_later(pasted, delay),
+        None => forget_saved_clipboard(),
+    }
+}
+
+/// Puts the saved clipboard back after `delay`, giving slow applications
+/// time to read the pasted text. If the clipboard no longer holds
+/// `injected`, the user copied something since and it is left alone.
+fn restore_clipboard_later(injected: String, delay: Duration) {
+    let generation = PASTE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    *PASTED_TEXT.lock().unwrap() = Some(injected.clone());
+    thread::spawn(move || {
+        thread::sleep(delay);
+        if PASTE_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        PASTED_TEXT.lock().unwrap().take();
+        let Some(snapshot) = SAVED_CLIPBOARD.lock().unwrap().take() else { return };
+        if clipboard_text().as_deref() != Some(injected.as_str()) {
+            info!("Clipboard changed since the paste, not restoring it");
+            return;
+        }
+        if let Err(e) = snapshot.restore() {
+            warn!("Failed to restore original clipboard: {}", e);
+        }
+    });
+}
+
+fn inject_text_via_clipboard(text: &str, delays: &PasteStrategy) -> Result<Injected, Box<dyn std::error::Error>> {
     use enigo::{Key, Direction};
     
     info!("Using balanced clipboard method to inject text: '{}'", text);
     
     // Store current clipboard content to restore later
-    let mut ctx: ClipboardContext = ClipboardProvider::new()
-        .map_err(|e| format!("Failed to initialize clipboard: {}", e))?;
-    
-    let original_clipboard = ctx.get_contents().unwrap_or_default();
-    
-    // Set our text to clipboard
-    ctx.set_contents(text.to_string())
-        .map_err(|e| format!("Failed to set clipboard content: {}", e))?;
+    let new_snapshot = save_clipboard();
+    if let Err(e) = with_clipboard(|clipboard| clipboard.set_text(text)) {
+        clipboard_not_set(new_snapshot, delays.restore_delay);
+        return Err(e.into());
+    }
     
     info!("Set text to clipboard, now sending balanced paste");
     
     // Get the injector instance for keyboard simulation
     let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
-    if let Some(injector) = injector_guard.as_mut() {
-        // Enhanced focus delay for Windows to ensure window is ready
+    let result = if let Some(injector) = injector_guard.as_mut() {
+        // Enhanced focus delay to ensure the window is ready
         let focus_delay = Duration::from_millis(300); // Increased for better reliability
         
         thread::sleep(focus_delay);
         
         // Try the clipboard approach first
         let clipboard_result = {
-            // Use Ctrl+V on Windows with enhanced timing
-            injector.key(Key::Control, Direction::Press)
+            // Use Ctrl+V (Cmd+V on macOS) with enhanced timing
+            let modifier = if cfg!(target_os = "macos") { Key::Meta } else { Key::Control };
+            injector.key(modifier, Direction::Press)
                 .and_then(|_| {
-                    thread::sleep(Duration::from_millis(50)); // Increased delay
+                    thread::sleep(delays.chord_delay);
                     injector.key(Key::Unicode('v'), Direction::Click)
                 })
                 .and_then(|_| {
-                    thread::sleep(Duration::from_millis(50)); // Increased delay
-                    injector.key(Key::Control, Direction::Release)
+                    thread::sleep(delays.chord_delay);
+                    injector.key(modifier, Direction::Release)
                 })
         };
         
         match clipboard_result {
             Ok(_) => {
                 info!("Balanced paste successful");
-                // Balanced wait time for paste completion
-                thread::sleep(Duration::from_millis(100)); // Balanced for Windows
+                Ok(Injected::Pasted)
             }
             Err(e) => {
                 warn!("Paste failed: {}, trying balanced character-by-character method", e);
-                
-                // Fallback: type character by character with balanced timing
-                info!("Using balanced character-by-character fallback for: '{}'", text);
-                for ch in text.chars() {
-                    match ch {
-                        '\n' => {
-                            injector.key(Key::Return, Direction::Click)
-                                .map_err(|e| format!("Failed to inject newline: {}", e))?;
-                        }
-                        '\t' => {
-                            injector.key(Key::Tab, Direction::Click)
-                                .map_err(|e| format!("Failed to inject tab: {}", e))?;
-                        }
-                        _ => {
-                            let char_str = ch.to_string();
-                            injector.text(&char_str)
-                                .map_err(|e| format!("Failed to inject character '{}': {}", ch, e))?;
-                        }
-                    }
-                    // Balanced delay between characters
-                    thread::sleep(Duration::from_millis(10)); // Balanced for Windows
-                }
-                info!("Balanced character-by-character injection completed");
-            }
-        }
-        
-        // Restore original clipboard content
-        if !original_clipboard.is_empty() {
-            if let Err(e) = ctx.set_contents(original_clipboard) {
-                warn!("Failed to restore original clipboard: {}", e);
+                type_with(injector, text, delays.fallback_key_delay).map(|_| {
+                    info!("Balanced character-by-character injection completed");
+                    Injected::Typed
+                })
             }
         }
-        
-        info!("Balanced clipboard-based text injection completed successfully");
+    } else {
+        Err("Text injector not initialized for clipboard method".to_string())
+    };
+
+    // Restore original clipboard content, even if the paste failed
+    restore_clipboard_later(text.to_string(), delays.restore_delay);
+    let injected = result?;
+    info!("Balanced clipboard-based text injection completed successfully");
+    Ok(injected)
+}
+
+// Injects text without touching the clipboard. On macOS this avoids crashes
+// related to NSPasteboard access off the main thread.
+fn inject_text_char_by_character(text: &str, key_delay: Duration) -> Result<(), Box<dyn std::error::Error>> {
+    // Get the global injector instance.
+    let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
+    if let Some(injector) = injector_guard.as_mut() {
+        type_with(injector, text, key_delay)?;
+        info!("Character-by-character injection completed successfully");
         Ok(())
     } else {
-        Err("Text injector not initialized for clipboard method".into())
+        Err("Text injector not initialised".into())
     }
 }
 
-// macOS-only helper that injects text without touching the clipboard. This avoids
-// crashes related to NSPasteboard access off the main thread.
-#[cfg(target_os = "macos")]
-fn inject_text_char_by_character(text: &str) -> Result<(), Box<dyn std::error::Error>> {
+fn type_with(injector: &mut Enigo, text: &str, key_delay: Duration) -> Result<(), String> {
     use enigo::{Key, Direction};
 
-    // Get the global injector instance.
-    let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
-    if let Some(injector) = injector_guard.as_mut() {
-        for ch in text.chars() {
-            match ch {
-                '\n' => {
-                    injector.key(Key::Return, Direction::Click)
-                        .map_err(|e| format!("Failed to inject newline: {}", e))?;
-                }
-                '\t' => {
-                    injector.key(Key::Tab, Direction::Click)
-                        .map_err(|e| format!("Failed to inject tab: {}", e))?;
-                }
-                _ => {
-                    // Enigo can type the whole remaining string at once, but we keep it
-                    // per-char for finer control and robustness.
-                    injector.text(&ch.to_string())
-                        .map_err(|e| format!("Failed to inject char '{}': {}", ch, e))?;
-                }
+    for ch in text.chars() {
+        match ch {
+            '\n' => {
+                injector.key(Key::Return, Direction::Click)
+                    .map_err(|e| format!("Failed to inject newline: {}", e))?;
+            }
+            '\t' => {
+                injector.key(Key::Tab, Direction::Click)
+                    .map_err(|e| format!("Failed to inject tab: {}", e))?;
+            }
+            _ => {
+                // Enigo can type the whole remaining string at once, but we keep it
+                // per-char for finer control and robustness.
+                injector.text(&ch.to_string())
+                    .map_err(|e| format!("Failed to inject char '{}': {}", ch, e))?;
             }
+        }
+
+        // Small delay so we don't overwhelm the target app.
+        thread::sleep(key_delay);
+    }
+    Ok(())
+}
+
+/// Erases the last `count` typed characters with Backspace.
+pub fn erase_typed(count: usize, key_delay: Duration) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if LinuxSession::detect() == LinuxSession::Wayland {
+        if has_command("wtype") {
+            let args: Vec<&str> = std::iter::repeat_n(["-k", "BackSpace"], count).flatten().collect();
+            return run_command("wtype", &args);
+        }
+        if has_command("ydotool") {
+            // 14 = KEY_BACKSPACE.
+            let delay = key_delay.as_millis().to_string();
+            let mut args = vec!["key", "--key-delay", &delay];
+            args.extend(std::iter::repeat_n(["14:1", "14:0"], count).flatten());
+            return run_command("ydotool", &args);
+        }
+    }
+
+    use enigo::{Direction, Key};
+    let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
+    let injector = injector_guard.as_mut().ok_or("Text injector not initialised")?;
+    for _ in 0..count {
+        injector
+            .key(Key::Backspace, Direction::Click)
+            .map_err(|e| format!("Failed to send backspace: {}", e))?;
+        thread::sleep(key_delay);
+    }
+    Ok(())
+}
 
-            // Small balanced delay so we don't overwhelm the target app.
-            std::thread::sleep(std::time::Duration::from_millis(6));
+/// Sends the application's undo shortcut, Ctrl+Z (Cmd+Z on macOS).
+pub fn send_undo_shortcut(chord_delay: Duration) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if LinuxSession::detect() == LinuxSession::Wayland {
+        if has_command("wtype") {
+            return run_command("wtype", &["-M", "ctrl", "z", "-m", "ctrl"]);
+        }
+        if has_command("ydotool") {
+            // 29 = KEY_LEFTCTRL, 44 = KEY_Z.
+            return run_command("ydotool", &["key", "29:1", "44:1", "44:0", "29:0"]);
         }
+    }
+
+    use enigo::{Direction, Key};
+    let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
+    let injector = injector_guard.as_mut().ok_or("Text injector not initialised")?;
+    let modifier = if cfg!(target_os = "macos") { Key::Meta } else { Key::Control };
+    injector
+        .key(modifier, Direction::Press)
+        .and_then(|_| {
+            thread::sleep(chord_delay);
+            injector.key(Key::Unicode('z'), Direction::Click)
+        })
+        .and_then(|_| {
+            thread::sleep(chord_delay);
+            injector.key(modifier, Direction::Release)
+        })
+        .map_err(|e| format!("Failed to send undo shortcut: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn has_command(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
 
-        info!("Character-by-character injection completed successfully on macOS");
+#[cfg(target_os = "linux")]
+fn run_command(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if output.status.success() {
         Ok(())
     } else {
-        Err("Text injector not initialised".into())
+        Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Types through wtype (the wlroots virtual-keyboard protocol) or ydotool
+/// (uinput, needs ydotoold), and falls back to pasting from the clipboard
+/// when neither is installed or the compositor rejects them.
+#[cfg(target_os = "linux")]
+fn inject_text_wayland(text: &str, key_delay: Duration) -> Result<Injected, Box<dyn std::error::Error>> {
+    let delay = key_delay.as_millis().to_string();
+    let typed = if has_command("wtype") {
+        run_command("wtype", &["-d", &delay, "--", text])
+    } else if has_command("ydotool") {
+        run_command("ydotool", &["type", "--key-delay", &delay, "--", text])
+    } else {
+        Err("no virtual keyboard helper installed".to_string())
+    };
+    match typed {
+        Ok(()) => {
+            info!("Wayland virtual keyboard injection completed successfully");
+            Ok(Injected::Typed)
+        }
+        Err(e) => {
+            warn!("Typing on Wayland failed: {}, falling back to clipboard paste", e);
+            inject_text_wayland_clipboard(text, Duration::from_millis(DEFAULT_CLIPBOARD_RESTORE_DELAY_MS))
+                .map(|_| Injected::Pasted)
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(target_os = "linux")]
+fn wl_copy(data: &[u8], mime: Option<&str>) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut command = Command::new("wl-copy");
+    if let Some(mime) = mime {
+        command.args(["--type", mime]);
+    }
+    // wl-copy forks to keep serving the selection, so don't wait on its output.
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run wl-copy: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(data).map_err(|e| e.to_string())?;
+    }
+    child.wait().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn wl_paste(mime: Option<&str>) -> Option<Vec<u8>> {
+    let mut command = std::process::Command::new("wl-paste");
+    command.arg("--no-newline");
+    if let Some(mime) = mime {
+        command.args(["--type", mime]);
+    }
+    command
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| output.stdout)
+}
+
+/// Reads the clipboard in the richest format it offers, in the same order
+/// as [`ClipboardSnapshot`].
+#[cfg(target_os = "linux")]
+fn wl_snapshot() -> Option<ClipboardSnapshot> {
+    let listed = std::process::Command::new("wl-paste").arg("--list-types").output().ok()?;
+    let types: Vec<String> = String::from_utf8_lossy(&listed.stdout).lines().map(str::to_string).collect();
+    let preferred = ["text/uri-list", "text/html", "image/png"]
+        .iter()
+        .find_map(|wanted| types.iter().find(|t| t == wanted))
+        .or_else(|| types.iter().find(|t| t.starts_with("image/")))
+        .or_else(|| types.iter().find(|t| t.starts_with("text/plain")))
+        .or_else(|| types.first())?
+        .clone();
+    let data = wl_paste(Some(&preferred)).filter(|data| !data.is_empty())?;
+    Some(ClipboardSnapshot::Wayland { mime: preferred, data })
+}
+
+#[cfg(target_os = "linux")]
+fn inject_text_wayland_clipboard(text: &str, restore_delay: Duration) -> Result<(), Box<dyn std::error::Error>> {
+    if !has_command("wl-copy") {
+        return Err("Cannot inject text on Wayland: install wtype, ydotool or wl-clipboard".into());
+    }
+
+    let new_snapshot = save_clipboard();
+    if let Err(e) = wl_copy(text.as_bytes(), None) {
+        clipboard_not_set(new_snapshot, restore_delay);
+        return Err(e.into());
+    }
+    thread::sleep(Duration::from_millis(50));
+
+    // Ctrl+V through whichever keyboard path exists; XWayland windows also
+    // accept it from enigo.
+    let pasted = if has_command("wtype") {
+        run_command("wtype", &["-M", "ctrl", "v", "-m", "ctrl"])
+    } else if has_command("ydotool") {
+        // Linux input event codes: 29 = KEY_LEFTCTRL, 47 = KEY_V.
+        run_command("ydotool", &["key", "29:1", "47:1", "47:0", "29:0"])
+    } else {
+        use enigo::{Direction, Key};
+        let mut injector_guard = TEXT_INJECTOR.lock().unwrap();
+        match injector_guard.as_mut() {
+            Some(injector) => injector
+                .key(Key::Control, Direction::Press)
+                .and_then(|_| injector.key(Key::Unicode('v'), Direction::Click))
+                .and_then(|_| injector.key(Key::Control, Direction::Release))
+                .map_err(|e| e.to_string()),
+            None => Err("no way to send Ctrl+V".to_string()),
+        }
+    };
+
+    if let Err(e) = pasted {
+        // Leave the text on the clipboard so the user can paste it by hand.
+        forget_saved_clipboard();
+        return Err(format!("Text copied to clipboard but could not be pasted: {}", e).into());
+    }
+
+    restore_clipboard_later(text.to_string(), restore_delay);
+    info!("Wayland clipboard-based text injection completed successfully");
+    Ok(())
+}
@@ -4,6 +4,14 @@ use reqwest::blocking::Client;
 use serde_json::Value;
 use std::time::Instant;
 use serde::{Serialize, Deserialize};
+use crate::settings::Settings;
+
+const GROQ_BASE_URL: &str = "https://api.groq.com/openai/v1";
+const GROQ_MODEL: &str = "whisper-large-v3-turbo";
+// Groq's turbo model cannot translate, only the full large-v3 can.
+const GROQ_TRANSLATION_MODEL: &str = "whisper-large-v3";
+const DEFAULT_LOCAL_BASE_URL: &str = "http://127.0.0.1:8080/v1";
+const DEFAULT_LOCAL_MODEL: &str = "whisper-1";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TranscriptionResult {
@@ -11,51 +19,135 @@ pub struct TranscriptionResult {
     pub round_trip_ms: u64,
     pub status: String,
     pub error: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
-pub fn transcribe_audio(file_path: &str, api_key: &str) -> TranscriptionResult {
-    let mut file = match File::open(file_path) {
-        Ok(f) => f,
-        Err(e) => {
-            return TranscriptionResult {
-                text: String::new(),
-                round_trip_ms: 0,
-                status: "error".to_string(),
-                error: Some(e.to_string()),
+impl TranscriptionResult {
+    fn failed(round_trip_ms: u64, error: String) -> Self {
+        Self {
+            text: String::new(),
+            round_trip_ms,
+            status: "error".to_string(),
+            error: Some(error),
+            language: None,
+        }
+    }
+}
+
+/// Whether speech is written down in the spoken language or translated to
+/// English via the `/audio/translations` endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Task {
+    Transcribe,
+    Translate,
+}
+
+impl Task {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Task::Transcribe => "transcribe",
+            Task::Translate => "translate",
+        }
+    }
+
+    fn endpoint(self) -> &'static str {
+        match self {
+            Task::Transcribe => "audio/transcriptions",
+            Task::Translate => "audio/translations",
+        }
+    }
+}
+
+/// An OpenAI-compatible speech-to-text endpoint. Groq is the default; "local"
+/// points at a self-hosted server (whisper.cpp, faster-whisper-server, LocalAI)
+/// so transcription also works offline.
+#[derive(Debug, Clone)]
+pub struct Provider {
+    pub name: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub translation_model: String,
+    pub language: Option<String>,
+}
+
+impl Provider {
+    pub fn from_settings(settings: &Settings) -> Result<Self, String> {
+        match settings.transcription_provider.as_deref().unwrap_or("groq") {
+            "groq" => {
+                let api_key = settings.groq_api_key.clone().ok_or("No Groq API key set")?;
+                Ok(Self {
+                    name: "Groq".to_string(),
+                    base_url: GROQ_BASE_URL.to_string(),
+                    api_key: Some(api_key),
+                    model: GROQ_MODEL.to_string(),
+                    translation_model: GROQ_TRANSLATION_MODEL.to_string(),
+                    language: settings.language.clone(),
+                })
             }
+            "local" => {
+                let model = settings
+                    .local_model
+                    .clone()
+                    .filter(|model| !model.trim().is_empty())
+                    .unwrap_or_else(|| DEFAULT_LOCAL_MODEL.to_string());
+                Ok(Self {
+                    name: "Local".to_string(),
+                    base_url: settings
+                        .local_endpoint
+                        .clone()
+                        .filter(|url| !url.trim().is_empty())
+                        .unwrap_or_else(|| DEFAULT_LOCAL_BASE_URL.to_string()),
+                    api_key: None,
+                    translation_model: model.clone(),
+                    model,
+                    language: settings.language.clone(),
+                })
+            }
+            other => Err(format!("Unknown transcription provider: {}", other)),
         }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), path)
+    }
+}
+
+pub fn transcribe_audio(file_path: &str, provider: &Provider, task: Task) -> TranscriptionResult {
+    let mut file = match File::open(file_path) {
+        Ok(f) => f,
+        Err(e) => return TranscriptionResult::failed(0, e.to_string()),
     };
     let mut buffer = Vec::new();
     if let Err(e) = file.read_to_end(&mut buffer) {
-        return TranscriptionResult {
-            text: String::new(),
-            round_trip_ms: 0,
-            status: "error".to_string(),
-            error: Some(e.to_string()),
-        }
+        return TranscriptionResult::failed(0, e.to_string());
     }
 
     let client = Client::new();
-    let url = "https://api.groq.com/openai/v1/audio/transcriptions";
-    let form = match reqwest::blocking::multipart::Form::new()
+    let url = provider.url(task.endpoint());
+    let model = match task {
+        Task::Transcribe => provider.model.clone(),
+        Task::Translate => provider.translation_model.clone(),
+    };
+    let mut form = match reqwest::blocking::multipart::Form::new()
         .file("file", file_path)
     {
-        Ok(f) => f.text("model", "whisper-large-v3-turbo").text("response_format", "json"),
-        Err(e) => {
-            return TranscriptionResult {
-                text: String::new(),
-                round_trip_ms: 0,
-                status: "error".to_string(),
-                error: Some(e.to_string()),
-            }
-        }
+        Ok(f) => f.text("model", model).text("response_format", "verbose_json"),
+        Err(e) => return TranscriptionResult::failed(0, e.to_string()),
     };
+    // The translations endpoint always outputs English and takes no language hint.
+    if let (Task::Transcribe, Some(language)) = (task, &provider.language) {
+        form = form.text("language", language.clone());
+    }
 
     let start = Instant::now();
-    let resp = client.post(url)
-        .multipart(form)
-        .bearer_auth(api_key)
-        .send();
+    let mut request = client.post(&url).multipart(form);
+    if let Some(api_key) = &provider.api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let resp = request.send();
     let duration = start.elapsed();
     let round_trip_ms = duration.as_millis() as u64;
     match resp {
@@ -63,46 +155,26 @@ pub fn transcribe_audio(file_path: &str, api_key: &str) -> TranscriptionResult {
             let status = resp.status();
             let text = match resp.text() {
                 Ok(t) => t,
-                Err(e) => {
-                    return TranscriptionResult {
-                        text: String::new(),
-                        round_trip_ms,
-                        status: "error".to_string(),
-                        error: Some(e.to_string()),
-                    }
-                }
+                Err(e) => return TranscriptionResult::failed(round_trip_ms, e.to_string()),
             };
             if !status.is_success() {
-                return TranscriptionResult {
-                    text: String::new(),
+                return TranscriptionResult::failed(
                     round_trip_ms,
-                    status: "error".to_string(),
-                    error: Some(format!("Groq API error: {} - {}", status, text)),
-                }
+                    format!("{} API error: {} - {}", provider.name, status, text),
+                );
             }
             let v: Value = match serde_json::from_str(&text) {
                 Ok(val) => val,
-                Err(e) => {
-                    return TranscriptionResult {
-                        text: String::new(),
-                        round_trip_ms,
-                        status: "error".to_string(),
-                        error: Some(e.to_string()),
-                    }
-                }
+                Err(e) => return TranscriptionResult::failed(round_trip_ms, e.to_string()),
             };
             TranscriptionResult {
                 text: v["text"].as_str().unwrap_or("").to_string(),
                 round_trip_ms,
                 status: "success".to_string(),
                 error: None,
+                language: v["language"].as_str().map(|l| l.to_string()),
             }
         }
-        Err(e) => TranscriptionResult {
-            text: String::new(),
-            round_trip_ms,
-            status: "error".to_string(),
-            error: Some(e.to_string()),
-        },
+        Err(e) => TranscriptionResult::failed(round_trip_ms, e.to_string()),
     }
-} 
\ No newline at end of file
+}
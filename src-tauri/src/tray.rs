@@ -1,19 +1,34 @@
 use tauri::{AppHandle, Runtime};
 use tauri::Manager;
 use tauri::tray::{TrayIcon, TrayIconBuilder};
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder};
+use crate::settings;
 
 pub fn create_system_tray<R: Runtime>(app: &AppHandle<R>) -> TrayIcon<R> {
     let dashboard_item = MenuItemBuilder::new("Dashboard")
         .id("dashboard")
         .build(app)
         .unwrap();
+    let translate_mode = settings::get_settings().map(|s| s.translate_mode).unwrap_or(false);
+    let translate_item = CheckMenuItemBuilder::new("Translate to English")
+        .id("translate")
+        .checked(translate_mode)
+        .build(app)
+        .unwrap();
+    let code_mode = settings::get_settings().map(|s| s.code_mode).unwrap_or(false);
+    let code_item = CheckMenuItemBuilder::new("Code mode")
+        .id("code")
+        .checked(code_mode)
+        .build(app)
+        .unwrap();
     let quit_item = MenuItemBuilder::new("Quit")
         .id("quit")
         .build(app)
         .unwrap();
     let menu = MenuBuilder::new(app)
         .item(&dashboard_item)
+        .item(&translate_item)
+        .item(&code_item)
         .separator()
         .item(&quit_item)
         .build()
@@ -21,12 +36,22 @@ pub fn create_system_tray<R: Runtime>(app: &AppHandle<R>) -> TrayIcon<R> {
     TrayIconBuilder::new()
         .icon(app.default_window_icon().unwrap().clone())
         .menu(&menu)
-        .on_menu_event(|app, event| {
+        .on_menu_event(move |app, event| {
             if event.id.0 == "dashboard" {
                 if let Some(window) = app.get_webview_window("dashboard") {
                     let _ = window.show();
                     let _ = window.set_focus();
                 }
+            } else if event.id.0 == "translate" {
+                let enabled = translate_item.is_checked().unwrap_or(false);
+                if let Err(e) = settings::set_translate_mode(enabled) {
+                    eprintln!("Failed to toggle translate mode: {}", e);
+                }
+            } else if event.id.0 == "code" {
+                let enabled = code_item.is_checked().unwrap_or(false);
+                if let Err(e) = settings::set_code_mode(enabled) {
+                    eprintln!("Failed to toggle code mode: {}", e);
+                }
             } else if event.id.0 == "quit" {
                 std::process::exit(0);
             }
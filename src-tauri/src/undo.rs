@@ -0,0 +1,138 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use chrono::Utc;
+use tauri::{command, AppHandle, Emitter};
+use unicode_segmentation::UnicodeSegmentation;
+use crate::focus::{self, ActiveWindow, FocusBackend};
+use crate::history::History;
+use crate::settings;
+use crate::textinjection::{self, Injected, InjectionConfig};
+use crate::HISTORY;
+
+/// The text most recently put into another application.
+struct LastDictation {
+    /// Characters as the user sees them (grapheme clusters, so an emoji
+    /// or an accented letter counts once), each one Backspace to erase.
+    chars: usize,
+    injected: Injected,
+    target: Option<ActiveWindow>,
+    history_id: Option<String>,
+}
+
+/// Only the latest dictation can be undone, and only once.
+static LAST: Mutex<Option<LastDictation>> = Mutex::new(None);
+
+pub fn record(text: &str, injected: Injected, target: Option<ActiveWindow>, history_id: Option<String>) {
+    *LAST.lock().unwrap() = Some(LastDictation { chars: text.graphemes(true).count(), injected, target, history_id });
+}
+
+/// Makes sure the keys for the undo go to `target`, bringing it back if
+/// the user clicked undo in our own window. Refused whenever the focused
+/// window can't be checked, since the keys would go who knows where.
+fn focus_target(focus: &dyn FocusBackend, target: Option<&ActiveWindow>) -> Result<(), String> {
+    if let Some(limitation) = focus.limitation() {
+        return Err(format!("Undo needs to know the focused window. {}", limitation));
+    }
+    let Some(target) = target else {
+        return Err("The window the text was dictated into is not known".to_string());
+    };
+    match focus.capture() {
+        // Clicked from our own window; go back to the target first.
+        Some(current) if current.is_own_window() => {
+            focus.restore(target)?;
+            thread::sleep(Duration::from_millis(100));
+        }
+        Some(current) if current.is_same_window(target) => {}
+        Some(_) => return Err("The window the text was dictated into is no longer focused".to_string()),
+        None => return Err("Could not tell which window is focused".to_string()),
+    }
+    Ok(())
+}
+
+/// Takes back the last dictation: Backspaces for typed text, the undo
+/// shortcut for a paste. Refused unless the dictation's window is known to
+/// have the focus; anywhere else either would delete the wrong thing. Bound to the undo hotkey.
+#[command]
+pub fn undo_last_dictation(app: AppHandle) -> Result<(), String> {
+    let Some(last) = LAST.lock().unwrap().take() else {
+        return Err("There is no dictation to undo".to_string());
+    };
+
+    if let Err(e) = focus_target(focus::backend(), last.target.as_ref()) {
+        *LAST.lock().unwrap() = Some(last);
+        return Err(e);
+    }
+
+    let config = settings::get_settings()
+        .map(|settings| InjectionConfig::from_settings(&settings))
+        .unwrap_or_default();
+    match last.injected {
+        Injected::Typed => textinjection::erase_typed(last.chars, config.key_delay)?,
+        Injected::Pasted => textinjection::send_undo_shortcut(config.chord_delay)?,
+    }
+
+    if let Some(id) = &last.history_id {
+        HISTORY.get_or_init(History::new).update_entry(id, |entry| entry.undone_at = Some(Utc::now()));
+    }
+    let _ = app.emit_to("main", "dictation-undone", last.history_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::focus::FakeFocus;
+
+    fn window(handle: isize) -> ActiveWindow {
+        ActiveWindow { handle: Some(handle), ..Default::default() }
+    }
+
+    #[test]
+    fn undoes_in_the_focused_target() {
+        let focus = FakeFocus::focused(window(1));
+        assert!(focus_target(&focus, Some(&window(1))).is_ok());
+        assert!(focus.restored_handles().is_empty());
+    }
+
+    #[test]
+    fn refuses_when_another_window_has_focus() {
+        let focus = FakeFocus::focused(window(2));
+        assert!(focus_target(&focus, Some(&window(1))).is_err());
+        assert!(focus.restored_handles().is_empty());
+    }
+
+    #[test]
+    fn refuses_when_the_target_is_unknown() {
+        let focus = FakeFocus::focused(window(1));
+        assert!(focus_target(&focus, None).is_err());
+    }
+
+    #[test]
+    fn refuses_when_the_focused_window_is_unknown() {
+        let focus = FakeFocus::default();
+        assert!(focus_target(&focus, Some(&window(1))).is_err());
+        assert!(focus.restored_handles().is_empty());
+    }
+
+    #[test]
+    fn refuses_when_focus_cannot_be_tracked() {
+        let focus = FakeFocus { limitation: Some("No focus tracking."), ..FakeFocus::focused(window(1)) };
+        let err = focus_target(&focus, Some(&window(1))).unwrap_err();
+        assert!(err.contains("No focus tracking."), "{}", err);
+    }
+
+    #[test]
+    fn counts_what_one_backspace_erases() {
+        record("e\u{301}👍🏽 ok\n", Injected::Typed, None, None);
+        assert_eq!(LAST.lock().unwrap().take().map(|last| last.chars), Some(6));
+    }
+
+    #[test]
+    fn goes_back_to_the_target_from_our_own_window() {
+        let own = ActiveWindow { handle: Some(9), pid: Some(std::process::id()), ..Default::default() };
+        let focus = FakeFocus::focused(own);
+        assert!(focus_target(&focus, Some(&window(1))).is_ok());
+        assert_eq!(focus.restored_handles(), vec![Some(1)]);
+    }
+}
@@ -29,7 +29,8 @@ export default function AudioPill() {
             state === "idle" && "px6 py-2",
             state === "listening" && "px-6 py-2",
             state === "loading" && "px-11 py-2",
-            state === "error" && "bg-red-600 px-6 py-2"
+            state === "error" && "bg-red-600 px-6 py-2",
+            state === "cancelled" && "bg-neutral-700 px-6 py-2"
           )}
         >
           {state === "error" && (
@@ -39,6 +40,13 @@ export default function AudioPill() {
             </div>
           )}
 
+          {state === "cancelled" && (
+            <div className="flex items-center gap-2">
+              <span className="text-white font-bold">Cancelled</span>
+              <X className="w-5 h-5 text-white" />
+            </div>
+          )}
+
           <div
             className={cn(
               "absolute right-3 transition-opacity duration-300",
@@ -56,7 +64,7 @@ export default function AudioPill() {
               <X className="w-5 h-5 text-red-500 hover:text-red-400" />
             </div>
           )}
-          {state !== "error" && <VoiceVisualizer />}
+          {state !== "error" && state !== "cancelled" && <VoiceVisualizer />}
         </div>
 
       </div>
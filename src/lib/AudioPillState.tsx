@@ -2,7 +2,7 @@ import { useEffect, useState, useRef } from "react"
 import { Window } from "@tauri-apps/api/window"
 import { soundManager } from "./sound"
 
-type AudioPillState = "idle" | "listening" | "loading" | "error" | "success"
+type AudioPillState = "idle" | "listening" | "loading" | "error" | "success" | "cancelled"
 
 type UnlistenFn = () => void
 